@@ -0,0 +1,628 @@
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+
+pub const DEFAULT_CONFIG_PATH: &str = "config/config.toml";
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub freshservice: FreshserviceConfig,
+    pub server: ServerConfig,
+    pub scrape: ScrapeConfig,
+    pub llm: LlmConfig,
+    pub scoring: ScoringWeights,
+    pub context: ContextConfig,
+    pub confidence: ConfidenceThresholds,
+    pub feedback: FeedbackConfig,
+    pub compliance: ComplianceConfig,
+    pub remote_docs: RemoteDocsConfig,
+    pub embeddings: EmbeddingsConfig,
+    /// Named Freshservice instances (`sandbox = "https://acme-sandbox.freshservice.com"`). A query
+    /// mentioning one gets a note to use that base URL.
+    pub environments: BTreeMap<String, String>,
+    /// Extra or overriding answer personas, name → system prompt snippet. See [`DEFAULT_PERSONAS`].
+    pub personas: BTreeMap<String, String>,
+}
+
+/// Personas available without any configuration. Each snippet is appended to the LLM's system
+/// prompt when a query asks for that persona.
+pub const DEFAULT_PERSONAS: [(&str, &str); 3] = [
+    ("developer", "Answer concisely for a developer: lead with the endpoint and a runnable curl example, and skip background explanations."),
+    ("support-agent", "Answer for an IT support agent: explain what the request does and when to use it in plain language before showing the technical details."),
+    ("beginner", "Answer for someone new to REST APIs: explain each step, define terms like endpoint, method and payload, and describe what a successful response looks like."),
+];
+
+impl Config {
+    /// The prompt snippet for persona `name`: a configured one if present, else a default.
+    pub fn persona(&self, name: &str) -> Option<&str> {
+        self.personas.get(name).map(String::as_str).or_else(|| {
+            DEFAULT_PERSONAS.iter().find(|(default, _)| *default == name).map(|(_, prompt)| *prompt)
+        })
+    }
+    
+    /// Every persona a query may pick, sorted.
+    pub fn persona_names(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = DEFAULT_PERSONAS.iter().map(|(name, _)| *name)
+            .chain(self.personas.keys().map(String::as_str))
+            .collect();
+        names.sort_unstable();
+        names.dedup();
+        names
+    }
+    
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read config file {}", path.display()))?;
+        toml::from_str(&content)
+            .with_context(|| format!("Failed to parse config file {}", path.display()))
+    }
+
+    /// Loads `path` when given, otherwise the default config file if present, otherwise built-in defaults.
+    pub fn load(path: Option<&Path>) -> Result<Self> {
+        match path {
+            Some(path) => Self::from_file(path),
+            None if Path::new(DEFAULT_CONFIG_PATH).exists() => Self::from_file(Path::new(DEFAULT_CONFIG_PATH)),
+            None => Ok(Self::default()),
+        }
+    }
+    
+    /// Checks the loaded values, returning every problem found rather than just the first.
+    pub fn validate(&self) -> Result<(), Vec<String>> {
+        let mut errors = Vec::new();
+        
+        if let Some(domain) = &self.freshservice.domain
+            && domain.contains(|c: char| c.is_whitespace() || c == '/' || c == ':')
+        {
+            errors.push(format!("freshservice.domain must be a bare host name, got '{}'", domain));
+        }
+        if self.server.port == 0 {
+            errors.push("server.port must be between 1 and 65535".to_string());
+        }
+        if self.llm.model.trim().is_empty() {
+            errors.push("llm.model must not be empty".to_string());
+        }
+        if self.llm.max_concurrent_requests == 0 {
+            errors.push("llm.max_concurrent_requests must be at least 1".to_string());
+        }
+        if let Err(e) = crate::llm::GroqClient::parse_headers(&self.llm.headers) {
+            errors.push(format!("llm.headers: {:#}", e));
+        }
+        if self.scrape.output.as_os_str().is_empty() {
+            errors.push("scrape.output must not be empty".to_string());
+        }
+        if self.scrape.sections.iter().all(|section| section.trim().is_empty()) {
+            errors.push("scrape.sections must name at least one section".to_string());
+        }
+        for (name, selector) in self.scrape.selectors.named() {
+            if let Err(e) = scraper::Selector::parse(&selector.replace("{section}", "ticket")) {
+                errors.push(format!("scrape.selectors.{} is not a valid CSS selector ('{}'): {}", name, selector, e));
+            }
+        }
+        
+        for (name, weight) in self.scoring.named() {
+            if !weight.is_finite() || weight < 0.0 {
+                errors.push(format!("scoring.{} must be a non-negative number, got {}", name, weight));
+            }
+        }
+        let total = self.scoring.total();
+        if !(total > 0.0 && total <= 100.0) {
+            errors.push(format!("scoring weights must sum to a value in (0, 100], got {}", total));
+        }
+        
+        if !(0.0..=1.0).contains(&self.scoring.score_floor) {
+            errors.push("scoring.score_floor must be between 0.0 and 1.0".to_string());
+        }
+        if self.scoring.max_returned == 0 {
+            errors.push("scoring.max_returned must be at least 1".to_string());
+        }
+        
+        if self.context.max_endpoints == 0 {
+            errors.push("context.max_endpoints must be at least 1".to_string());
+        }
+        if self.context.token_budget == 0 {
+            errors.push("context.token_budget must be at least 1".to_string());
+        }
+        if !(0.0..=1.0).contains(&self.context.min_score_ratio) {
+            errors.push("context.min_score_ratio must be between 0.0 and 1.0".to_string());
+        }
+        if !(0.0..=1.0).contains(&self.context.description_similarity) {
+            errors.push("context.description_similarity must be between 0.0 and 1.0".to_string());
+        }
+        
+        let thresholds = &self.confidence;
+        if !(0.0..=1.0).contains(&thresholds.high) || !(0.0..=1.0).contains(&thresholds.medium) {
+            errors.push("confidence thresholds must be between 0.0 and 1.0".to_string());
+        } else if thresholds.medium > thresholds.high {
+            errors.push("confidence.medium must not exceed confidence.high".to_string());
+        }
+        
+        for entry in &self.compliance.blocked_endpoints {
+            let pattern = entry.split_once(' ').map_or(entry.as_str(), |(_, rest)| rest.trim());
+            if let Err(e) = glob::Pattern::new(pattern) {
+                errors.push(format!("compliance.blocked_endpoints entry '{}' is not a valid pattern: {}", entry, e));
+            }
+        }
+        
+        for (name, prompt) in &self.personas {
+            if prompt.trim().is_empty() {
+                errors.push(format!("personas.{} must not be empty", name));
+            }
+        }
+        for (name, url) in &self.environments {
+            if !(url.starts_with("https://") || url.starts_with("http://")) {
+                errors.push(format!("environments.{} must be an http(s) URL, got '{}'", name, url));
+            }
+        }
+        
+        let feedback = &self.feedback;
+        if !(feedback.boost_per_vote.is_finite() && feedback.boost_per_vote >= 0.0) {
+            errors.push("feedback.boost_per_vote must be a non-negative number".to_string());
+        }
+        if !(0.0..=1.0).contains(&feedback.max_boost) {
+            errors.push("feedback.max_boost must be between 0.0 and 1.0".to_string());
+        }
+        
+        if !(0.0..=1.0).contains(&self.embeddings.weight) {
+            errors.push("embeddings.weight must be between 0.0 and 1.0".to_string());
+        }
+        if self.remote_docs.cache.as_os_str().is_empty() {
+            errors.push("remote_docs.cache must not be empty".to_string());
+        }
+        
+        if errors.is_empty() { Ok(()) } else { Err(errors) }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct FreshserviceConfig {
+    /// Your Freshservice host (`acme` or `acme.freshservice.com`). When set, placeholder hosts in
+    /// curl examples and answers are replaced with it.
+    pub domain: Option<String>,
+}
+
+impl FreshserviceConfig {
+    /// The configured domain as a full host name, if any.
+    pub fn host(&self) -> Option<String> {
+        let domain = self.domain.as_deref()?.trim();
+        if domain.is_empty() {
+            None
+        } else if domain.contains('.') {
+            Some(domain.to_string())
+        } else {
+            Some(format!("{}.freshservice.com", domain))
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct ServerConfig {
+    pub port: u16,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self { port: 8080 }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct ScrapeConfig {
+    pub output: PathBuf,
+    /// Docs sections to scrape, e.g. `ticket`, `problem`, `change`.
+    pub sections: Vec<String>,
+    pub selectors: ScraperSelectors,
+    /// A section yielding fewer endpoints than this is topped up from the curated fallback.
+    pub min_real_endpoints: usize,
+    /// Warn about curl example bodies that disagree with the parameter tables after a scrape.
+    pub validate_examples: bool,
+}
+
+impl Default for ScrapeConfig {
+    fn default() -> Self {
+        Self {
+            output: PathBuf::from("data/scraped/documentation.json"),
+            sections: vec!["ticket".to_string()],
+            selectors: ScraperSelectors::default(),
+            min_real_endpoints: 3,
+            validate_examples: true,
+        }
+    }
+}
+
+/// CSS selectors the scraper uses to find endpoints in the docs page, so markup changes on the
+/// doc site can be handled from the config file. `{section}` in `section` and `container` is
+/// replaced with the section being scraped.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct ScraperSelectors {
+    /// One element per endpoint section.
+    pub section: String,
+    /// Container searched for curl examples when sections yield nothing.
+    pub container: String,
+    pub code_block: String,
+    pub table: String,
+    /// Endpoint title inside a section.
+    pub heading: String,
+    /// Note / warning callouts inside a section.
+    pub note: String,
+}
+
+impl Default for ScraperSelectors {
+    fn default() -> Self {
+        Self {
+            section: "div[id*='{section}']".to_string(),
+            container: "div#{section}s".to_string(),
+            code_block: "pre, .highlight".to_string(),
+            table: "table".to_string(),
+            heading: "h2".to_string(),
+            note: ".note, .warning, .caution, .admonition, .callout, blockquote".to_string(),
+        }
+    }
+}
+
+impl ScraperSelectors {
+    fn named(&self) -> [(&'static str, &str); 6] {
+        [
+            ("section", &self.section),
+            ("container", &self.container),
+            ("code_block", &self.code_block),
+            ("table", &self.table),
+            ("heading", &self.heading),
+            ("note", &self.note),
+        ]
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct LlmConfig {
+    pub model: String,
+    /// LLM calls allowed in flight at once; further `/query` requests wait for a free slot.
+    pub max_concurrent_requests: usize,
+    /// Extra HTTP headers sent with every completion request, e.g. for an LLM gateway.
+    pub headers: HashMap<String, String>,
+}
+
+impl Default for LlmConfig {
+    fn default() -> Self {
+        Self {
+            model: "llama-3.3-70b-versatile".to_string(),
+            max_concurrent_requests: 8,
+            headers: HashMap::new(),
+        }
+    }
+}
+
+/// Maximum contribution of each signal to the relevance score. The final score is
+/// normalized by the sum of the core signal weights, so only their ratios affect ranking.
+/// Bonus signals (`status_code`) are added on top without widening that sum.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct ScoringWeights {
+    pub name: f32,
+    pub description: f32,
+    pub path: f32,
+    pub method: f32,
+    pub parameters: f32,
+    pub curl: f32,
+    pub status_code: f32,
+    /// How queries are split into terms before matching.
+    pub tokenizer: TokenizerKind,
+    /// Matches scoring at or below this (normalized, 0.0-1.0) are dropped outright.
+    pub score_floor: f32,
+    /// Most matches a query is answered from, best first; searches take their own limit.
+    pub max_returned: usize,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TokenizerKind {
+    /// Drops English stopwords and plural endings.
+    #[default]
+    English,
+    /// Plain whitespace split, for docs in other languages.
+    Whitespace,
+}
+
+impl Default for ScoringWeights {
+    fn default() -> Self {
+        Self {
+            name: 2.5,
+            description: 1.3,
+            path: 0.8,
+            method: 0.8,
+            parameters: 0.6,
+            curl: 1.0,
+            status_code: 0.5,
+            tokenizer: TokenizerKind::default(),
+            score_floor: 0.1,
+            max_returned: 50,
+        }
+    }
+}
+
+impl ScoringWeights {
+    fn named(&self) -> [(&'static str, f32); 7] {
+        [
+            ("name", self.name),
+            ("description", self.description),
+            ("path", self.path),
+            ("method", self.method),
+            ("parameters", self.parameters),
+            ("curl", self.curl),
+            ("status_code", self.status_code),
+        ]
+    }
+    
+    /// Sum of the core signal weights, used to normalize scores.
+    pub fn total(&self) -> f32 {
+        self.name + self.description + self.path + self.method + self.parameters + self.curl
+    }
+}
+
+/// Limits on how many matched endpoints are packed into the LLM context.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct ContextConfig {
+    pub max_endpoints: usize,
+    /// Approximate token budget for the formatted context.
+    pub token_budget: usize,
+    /// Stop adding endpoints once one scores below this fraction of the top score.
+    pub min_score_ratio: f32,
+    /// Word overlap (Jaccard, 0.0-1.0) above which descriptions of included endpoints are
+    /// printed once as a shared note instead of per endpoint.
+    pub description_similarity: f32,
+}
+
+impl Default for ContextConfig {
+    fn default() -> Self {
+        Self {
+            max_endpoints: 5,
+            token_budget: 2000,
+            min_score_ratio: 0.5,
+            description_similarity: 0.8,
+        }
+    }
+}
+
+/// Score cut-offs used to bucket the numeric confidence into a coarse level for UIs.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct ConfidenceThresholds {
+    pub high: f32,
+    pub medium: f32,
+}
+
+impl Default for ConfidenceThresholds {
+    fn default() -> Self {
+        Self { high: 0.7, medium: 0.4 }
+    }
+}
+
+impl ConfidenceThresholds {
+    pub fn level(&self, confidence: f32) -> &'static str {
+        if confidence >= self.high {
+            "high"
+        } else if confidence >= self.medium {
+            "medium"
+        } else {
+            "low"
+        }
+    }
+}
+
+/// Where answer ratings are logged and how much they may lift an endpoint's score.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct FeedbackConfig {
+    pub log: PathBuf,
+    /// Score added per net helpful rating of an answer that used the endpoint.
+    pub boost_per_vote: f32,
+    /// Upper bound on an endpoint's total feedback boost.
+    pub max_boost: f32,
+}
+
+impl Default for FeedbackConfig {
+    fn default() -> Self {
+        Self {
+            log: PathBuf::from("data/feedback.jsonl"),
+            boost_per_vote: 0.02,
+            max_boost: 0.1,
+        }
+    }
+}
+
+/// Endpoint embeddings used for a similarity bonus on top of the keyword score.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct EmbeddingsConfig {
+    pub enabled: bool,
+    /// Vectors of the last loaded catalog, reused for endpoints whose content is unchanged.
+    pub cache: PathBuf,
+    /// Most the similarity may add to an endpoint's normalized score.
+    pub weight: f32,
+}
+
+impl Default for EmbeddingsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            cache: PathBuf::from("data/cache/embeddings.json"),
+            weight: 0.1,
+        }
+    }
+}
+
+/// How documentation loaded from a URL (`--docs https://...`) is cached and what happens when
+/// the fetch fails.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct RemoteDocsConfig {
+    /// Local copy of the last fetched catalog.
+    pub cache: PathBuf,
+    /// Seconds the cached copy is served before the URL is fetched again.
+    pub ttl_secs: u64,
+    /// Scrape the docs site live when the URL cannot be fetched, instead of failing.
+    pub fallback_to_live: bool,
+}
+
+impl Default for RemoteDocsConfig {
+    fn default() -> Self {
+        Self {
+            cache: PathBuf::from("data/cache/remote_documentation.json"),
+            ttl_secs: 3600,
+            fallback_to_live: false,
+        }
+    }
+}
+
+/// Endpoints that must never appear in answers, context, or listings.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ComplianceConfig {
+    /// Endpoint names/aliases or path globs, optionally prefixed with a method (`DELETE *`).
+    pub blocked_endpoints: Vec<String>,
+    /// Text put before every `/query` answer, e.g. a disclaimer. Empty by default.
+    pub answer_prefix: String,
+    /// Text put after every `/query` answer. Empty by default.
+    pub answer_suffix: String,
+}
+
+impl ComplianceConfig {
+    /// `answer` between the configured prefix and suffix, each separated by a blank line.
+    pub fn wrap_answer(&self, answer: &str) -> String {
+        [self.answer_prefix.trim(), answer, self.answer_suffix.trim()]
+            .into_iter()
+            .filter(|part| !part.is_empty())
+            .collect::<Vec<_>>()
+            .join("\n\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_confidence_level_boundaries() {
+        let thresholds = ConfidenceThresholds::default();
+
+        assert_eq!(thresholds.level(1.0), "high");
+        assert_eq!(thresholds.level(0.7), "high");
+        assert_eq!(thresholds.level(0.69), "medium");
+        assert_eq!(thresholds.level(0.4), "medium");
+        assert_eq!(thresholds.level(0.39), "low");
+        assert_eq!(thresholds.level(0.1), "low");
+    }
+
+    #[test]
+    fn test_wrap_answer_with_prefix_and_suffix() {
+        let mut compliance = ComplianceConfig::default();
+        assert_eq!(compliance.wrap_answer("Use POST /api/v2/tickets."), "Use POST /api/v2/tickets.");
+
+        compliance.answer_suffix = "Verify against official docs before use.".to_string();
+        assert_eq!(
+            compliance.wrap_answer("Use POST /api/v2/tickets."),
+            "Use POST /api/v2/tickets.\n\nVerify against official docs before use."
+        );
+
+        compliance.answer_prefix = "Internal use only.\n".to_string();
+        assert_eq!(
+            compliance.wrap_answer("Use POST /api/v2/tickets."),
+            "Internal use only.\n\nUse POST /api/v2/tickets.\n\nVerify against official docs before use."
+        );
+    }
+
+    #[test]
+    fn test_confidence_thresholds_from_toml() {
+        let config: Config = toml::from_str("[confidence]\nhigh = 0.8\n").unwrap();
+
+        assert_eq!(config.confidence.high, 0.8);
+        assert_eq!(config.confidence.medium, 0.4);
+        assert_eq!(config.confidence.level(0.75), "medium");
+    }
+    
+    #[test]
+    fn test_freshservice_host() {
+        let short: Config = toml::from_str("[freshservice]\ndomain = \"acme\"\n").unwrap();
+        let full: Config = toml::from_str("[freshservice]\ndomain = \"help.acme.io\"\n").unwrap();
+        
+        assert_eq!(short.freshservice.host().as_deref(), Some("acme.freshservice.com"));
+        assert_eq!(full.freshservice.host().as_deref(), Some("help.acme.io"));
+        assert_eq!(Config::default().freshservice.host(), None);
+    }
+    
+    #[test]
+    fn test_default_config_is_valid() {
+        assert!(Config::default().validate().is_ok());
+    }
+    
+    #[test]
+    fn test_validate_reports_every_problem() {
+        let config: Config = toml::from_str(
+            "[server]\nport = 0\n[llm]\nmodel = \" \"\n[scoring]\nname = -1.0\n[confidence]\nhigh = 0.3\nmedium = 0.5\n",
+        ).unwrap();
+        
+        let errors = config.validate().unwrap_err();
+        
+        assert!(errors.iter().any(|e| e.contains("server.port")));
+        assert!(errors.iter().any(|e| e.contains("llm.model")));
+        assert!(errors.iter().any(|e| e.contains("scoring.name")));
+        assert!(errors.iter().any(|e| e.contains("confidence.medium")));
+    }
+    
+    #[test]
+    fn test_environments_from_toml() {
+        let config: Config = toml::from_str(
+            "[environments]\nsandbox = \"https://acme-sandbox.freshservice.com\"\nstaging = \"acme-staging.freshservice.com\"\n",
+        ).unwrap();
+        
+        assert_eq!(config.environments["sandbox"], "https://acme-sandbox.freshservice.com");
+        let errors = config.validate().unwrap_err();
+        assert_eq!(errors, vec!["environments.staging must be an http(s) URL, got 'acme-staging.freshservice.com'".to_string()]);
+    }
+    
+    #[test]
+    fn test_custom_personas_extend_defaults() {
+        let config: Config = toml::from_str(
+            "[personas]\nauditor = \"Point out permissions each call needs.\"\ndeveloper = \"Just the curl.\"\n",
+        ).unwrap();
+        
+        assert_eq!(config.persona("auditor"), Some("Point out permissions each call needs."));
+        assert_eq!(config.persona("developer"), Some("Just the curl."));
+        assert!(config.persona("beginner").unwrap().contains("new to REST APIs"));
+        assert_eq!(config.persona("pirate"), None);
+        assert_eq!(config.persona_names(), vec!["auditor", "beginner", "developer", "support-agent"]);
+    }
+    
+    #[test]
+    fn test_invalid_selector_is_rejected() {
+        let config: Config = toml::from_str("[scrape.selectors]\ncode_block = \"pre[\"\n").unwrap();
+        
+        let errors = config.validate().unwrap_err();
+        
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].starts_with("scrape.selectors.code_block"));
+        assert_eq!(config.scrape.selectors.table, "table");
+    }
+    
+    #[test]
+    fn test_invalid_llm_headers_are_rejected() {
+        let config: Config = toml::from_str("[llm.headers]
+\"X-Org-Id\" = \"acme\"
+").unwrap();
+        assert!(config.validate().is_ok());
+        
+        let config: Config = toml::from_str("[llm.headers]
+\"Content-Type\" = \"text/plain\"
+").unwrap();
+        let errors = config.validate().unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].starts_with("llm.headers"));
+    }
+}
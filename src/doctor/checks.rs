@@ -0,0 +1,129 @@
+use crate::config::Config;
+use crate::llm::GroqClient;
+use crate::scraper::FreshserviceScraper;
+
+/// Outcome of one `doctor` check: what was found on success, or the problem plus a hint on failure.
+struct Check {
+    name: &'static str,
+    outcome: Result<String, (String, &'static str)>,
+}
+
+impl Check {
+    fn pass(name: &'static str, detail: impl Into<String>) -> Self {
+        Self { name, outcome: Ok(detail.into()) }
+    }
+
+    fn fail(name: &'static str, problem: impl Into<String>, hint: &'static str) -> Self {
+        Self { name, outcome: Err((problem.into(), hint)) }
+    }
+}
+
+/// Runs every setup check, prints a pass/fail checklist, and returns whether all passed.
+pub async fn run_doctor(config: &Config, port: u16) -> bool {
+    let scraper = FreshserviceScraper::new()
+        .with_selectors(config.scrape.selectors.clone())
+        .with_min_real_endpoints(config.scrape.min_real_endpoints);
+
+    let checks = vec![
+        check_docs_reachable(&scraper).await,
+        check_scrape(&scraper, &config.scrape.sections).await,
+        check_groq_key(config).await,
+        check_port(port),
+    ];
+
+    let mut all_passed = true;
+    for check in &checks {
+        match &check.outcome {
+            Ok(detail) => println!("[PASS] {}: {}", check.name, detail),
+            Err((problem, hint)) => {
+                all_passed = false;
+                println!("[FAIL] {}: {}", check.name, problem);
+                println!("       hint: {}", hint);
+            }
+        }
+    }
+    all_passed
+}
+
+async fn check_docs_reachable(scraper: &FreshserviceScraper) -> Check {
+    const NAME: &str = "Docs host reachable";
+    match scraper.check_reachable().await {
+        Ok(status) if status < 400 => Check::pass(NAME, format!("HTTP {}", status)),
+        Ok(status) => Check::fail(NAME, format!("HTTP {}", status), "The docs site answered with an error; try again later."),
+        Err(e) => Check::fail(NAME, describe(&e), "Check your network connection, proxy settings, and DNS."),
+    }
+}
+
+async fn check_scrape(scraper: &FreshserviceScraper, sections: &[String]) -> Check {
+    const NAME: &str = "Scraping finds endpoints";
+    let scrape = scraper.scrape_sections(sections).await;
+    if let Some(failure) = scrape.failed.first() {
+        return Check::fail(
+            NAME,
+            format!("section '{}' failed: {}", failure.section, failure.error),
+            "Fix the docs host check above first, or remove the section from scrape.sections.",
+        );
+    }
+    // Curated fallback entries would mask a broken scrape, so only live ones count
+    match scrape.documentation.endpoints.iter().filter(|endpoint| !endpoint.fallback).count() {
+        0 => Check::fail(
+            NAME,
+            "0 endpoints extracted",
+            "The docs markup may have changed; adjust [scrape.selectors] in the config file.",
+        ),
+        count => Check::pass(NAME, format!("{} endpoints", count)),
+    }
+}
+
+async fn check_groq_key(config: &Config) -> Check {
+    const NAME: &str = "Groq API key";
+    let Some(api_key) = GroqClient::api_key_from_env() else {
+        return Check::fail(
+            NAME,
+            "GROQ_API_KEY is not set",
+            "export GROQ_API_KEY=<key from https://console.groq.com/keys> (without it, answers echo the docs).",
+        );
+    };
+    let client = match GroqClient::new(api_key).with_model(config.llm.model.clone()).with_headers(config.llm.headers.clone()) {
+        Ok(client) => client,
+        Err(e) => return Check::fail(NAME, format!("{:#}", e), "Fix the header names and values under [llm.headers]."),
+    };
+    match client.preflight().await {
+        Ok(()) => Check::pass(NAME, format!("preflight succeeded with model {}", config.llm.model)),
+        Err(e) => Check::fail(NAME, e.to_string(), "Check the key is current and llm.model is a model your account can use."),
+    }
+}
+
+/// The top-level error plus its root cause; the full chain of network errors is too noisy for a checklist.
+fn describe(error: &anyhow::Error) -> String {
+    let root = error.root_cause().to_string();
+    let top = error.to_string();
+    if root == top { top } else { format!("{} ({})", top, root) }
+}
+
+fn check_port(port: u16) -> Check {
+    const NAME: &str = "Server port bindable";
+    match std::net::TcpListener::bind(("127.0.0.1", port)) {
+        Ok(_) => Check::pass(NAME, format!("127.0.0.1:{}", port)),
+        Err(e) => Check::fail(
+            NAME,
+            format!("127.0.0.1:{}: {}", port, e),
+            "Stop whatever is using the port, or pick another with --port or server.port.",
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_port_check_fails_when_port_is_taken() {
+        let listener = std::net::TcpListener::bind(("127.0.0.1", 0)).unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        assert!(check_port(port).outcome.is_err());
+        drop(listener);
+        assert!(check_port(port).outcome.is_ok());
+    }
+}
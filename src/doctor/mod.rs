@@ -0,0 +1,3 @@
+mod checks;
+
+pub use checks::run_doctor;
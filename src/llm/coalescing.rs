@@ -0,0 +1,135 @@
+use super::{GenerationOptions, LlmAnswer, LlmProvider};
+use anyhow::Result;
+use async_trait::async_trait;
+use futures::future::{BoxFuture, FutureExt, Shared};
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, Mutex};
+
+type InFlightKey = (String, u64, GenerationOptions);
+type InFlightAnswer = Shared<BoxFuture<'static, Result<LlmAnswer, String>>>;
+
+/// Wraps a provider so identical concurrent requests share a single upstream call.
+///
+/// Requests are keyed on the query, a hash of the context, and the generation options; a second caller arriving while
+/// the first is still waiting on the LLM awaits the same future instead of issuing its own. Only the caller that issued
+/// the call gets its token usage; the others report none, so the tokens are counted once.
+pub struct CoalescingProvider {
+    inner: Arc<dyn LlmProvider>,
+    in_flight: Mutex<HashMap<InFlightKey, InFlightAnswer>>,
+}
+
+impl CoalescingProvider {
+    pub fn new(inner: Arc<dyn LlmProvider>) -> Self {
+        Self {
+            inner,
+            in_flight: Mutex::new(HashMap::new()),
+        }
+    }
+    
+    fn key(query: &str, context: &str, options: &GenerationOptions) -> InFlightKey {
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        context.hash(&mut hasher);
+        (query.to_string(), hasher.finish(), options.clone())
+    }
+}
+
+#[async_trait]
+impl LlmProvider for CoalescingProvider {
+    async fn generate_answer(&self, query: &str, context: &str, options: &GenerationOptions) -> Result<LlmAnswer> {
+        let key = Self::key(query, context, options);
+        
+        let mut issued = false;
+        let answer = {
+            let mut in_flight = self.in_flight.lock().unwrap_or_else(|e| e.into_inner());
+            in_flight.entry(key.clone())
+                .or_insert_with(|| {
+                    issued = true;
+                    let inner = self.inner.clone();
+                    let query = query.to_string();
+                    let context = context.to_string();
+                    let options = options.clone();
+                    async move {
+                        inner.generate_answer(&query, &context, &options).await.map_err(|e| e.to_string())
+                    }
+                    .boxed()
+                    .shared()
+                })
+                .clone()
+        };
+        
+        let result = answer.clone().await;
+        
+        // The first waiter to finish clears the entry so later requests hit the LLM afresh
+        let mut in_flight = self.in_flight.lock().unwrap_or_else(|e| e.into_inner());
+        if in_flight.get(&key).is_some_and(|current| current.ptr_eq(&answer)) {
+            in_flight.remove(&key);
+        }
+        
+        result
+            .map(|answer| if issued { answer } else { LlmAnswer { usage: None, ..answer } })
+            .map_err(anyhow::Error::msg)
+    }
+    
+    fn cacheable(&self) -> bool {
+        self.inner.cacheable()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::TokenUsage;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    
+    struct SlowCountingProvider {
+        calls: AtomicUsize,
+    }
+    
+    #[async_trait]
+    impl LlmProvider for SlowCountingProvider {
+        async fn generate_answer(&self, query: &str, _context: &str, _options: &GenerationOptions) -> Result<LlmAnswer> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+            Ok(LlmAnswer {
+                text: format!("answer to {}", query),
+                usage: Some(TokenUsage { prompt: 10, completion: 5, total: 15 }),
+            })
+        }
+    }
+    
+    #[tokio::test]
+    async fn test_identical_concurrent_queries_share_one_call() {
+        let mock = Arc::new(SlowCountingProvider { calls: AtomicUsize::new(0) });
+        let provider = CoalescingProvider::new(mock.clone());
+        let options = GenerationOptions::default();
+        
+        let (first, second) = tokio::join!(
+            provider.generate_answer("create ticket", "ctx", &options),
+            provider.generate_answer("create ticket", "ctx", &options),
+        );
+        
+        let (first, second) = (first.unwrap(), second.unwrap());
+        assert_eq!(first.text, "answer to create ticket");
+        assert_eq!(second.text, "answer to create ticket");
+        assert_eq!(mock.calls.load(Ordering::SeqCst), 1);
+        // The shared call's tokens are reported once
+        assert_eq!(first.usage.is_some() as usize + second.usage.is_some() as usize, 1);
+        assert!(provider.in_flight.lock().unwrap().is_empty());
+    }
+    
+    #[tokio::test]
+    async fn test_different_context_is_not_coalesced() {
+        let mock = Arc::new(SlowCountingProvider { calls: AtomicUsize::new(0) });
+        let provider = CoalescingProvider::new(mock.clone());
+        let options = GenerationOptions::default();
+        
+        let (first, second) = tokio::join!(
+            provider.generate_answer("create ticket", "ctx a", &options),
+            provider.generate_answer("create ticket", "ctx b", &options),
+        );
+        
+        assert!(first.is_ok() && second.is_ok());
+        assert_eq!(mock.calls.load(Ordering::SeqCst), 2);
+    }
+}
@@ -0,0 +1,33 @@
+use super::{GenerationOptions, LlmAnswer, LlmProvider};
+use anyhow::Result;
+use async_trait::async_trait;
+
+const NO_LLM_BANNER: &str = "No LLM is configured (GROQ_API_KEY is not set), so no answer was generated. \
+    Here is the most relevant documentation for your question:";
+
+/// Offline provider that returns the retrieved context verbatim instead of calling an LLM.
+pub struct EchoContextClient;
+
+#[async_trait]
+impl LlmProvider for EchoContextClient {
+    async fn generate_answer(&self, _query: &str, context: &str, _options: &GenerationOptions) -> Result<LlmAnswer> {
+        Ok(LlmAnswer::text(format!("{}\n\n{}", NO_LLM_BANNER, context.trim())))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[tokio::test]
+    async fn test_echoes_context_with_banner() {
+        let answer = EchoContextClient
+            .generate_answer("create ticket", "Create Ticket (POST)\nPath: /api/v2/tickets\n", &GenerationOptions::default())
+            .await
+            .unwrap()
+            .text;
+        
+        assert!(answer.starts_with(NO_LLM_BANNER));
+        assert!(answer.ends_with("Path: /api/v2/tickets"));
+    }
+}
@@ -1,20 +1,169 @@
-use anyhow::Result;
+use super::{GenerationOptions, LlmAnswer, LlmProvider, TokenUsage};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, AUTHORIZATION, CONTENT_TYPE};
 use serde_json::json;
+use std::collections::HashMap;
+use thiserror::Error;
+
+const GROQ_CHAT_URL: &str = "https://api.groq.com/openai/v1/chat/completions";
+const PLACEHOLDER_API_KEY: &str = "gsk_placeholder_key";
+const SYSTEM_PROMPT: &str = "You are an expert on Freshservice API documentation. Provide accurate, helpful answers based on the given context.";
+/// Seed sent with deterministic requests; any fixed value works, it just has to stay the same.
+const DETERMINISTIC_SEED: u64 = 42;
+
+#[derive(Debug, Error)]
+pub enum GroqError {
+    #[error("Groq API rejected the API key ({status}): {body}")]
+    Unauthorized { status: u16, body: String },
+    #[error("Groq API error ({status}): {body}")]
+    Api { status: u16, body: String },
+}
+
+impl GroqError {
+    pub fn is_auth(&self) -> bool {
+        matches!(self, GroqError::Unauthorized { .. })
+    }
+}
 
 pub struct GroqClient {
     api_key: String,
+    model: String,
     client: reqwest::Client,
+    chat_url: String,
+    headers: HeaderMap,
 }
 
 impl GroqClient {
     pub fn new(api_key: String) -> Self {
         Self {
             api_key,
+            model: "llama-3.3-70b-versatile".to_string(),
             client: reqwest::Client::new(),
+            chat_url: GROQ_CHAT_URL.to_string(),
+            headers: HeaderMap::new(),
+        }
+    }
+    
+    /// The `GROQ_API_KEY` environment variable, treating an empty value or the old placeholder as unset.
+    pub fn api_key_from_env() -> Option<String> {
+        std::env::var("GROQ_API_KEY")
+            .ok()
+            .map(|key| key.trim().to_string())
+            .filter(|key| !key.is_empty() && key != PLACEHOLDER_API_KEY)
+    }
+    
+    pub fn with_model(mut self, model: impl Into<String>) -> Self {
+        self.model = model.into();
+        self
+    }
+    
+    /// Extra headers sent with every completion request, e.g. an org ID or routing key required by
+    /// an LLM gateway. Fails as [`Self::parse_headers`] does.
+    pub fn with_headers(mut self, headers: HashMap<String, String>) -> Result<Self> {
+        self.headers.extend(Self::parse_headers(&headers)?);
+        Ok(self)
+    }
+    
+    /// `headers` as HTTP headers. Fails on names or values that aren't valid HTTP, and on
+    /// `Authorization` and `Content-Type`, which the client always sets itself.
+    pub fn parse_headers(headers: &HashMap<String, String>) -> Result<HeaderMap> {
+        let mut header_map = HeaderMap::new();
+        for (name, value) in headers {
+            let header_name = HeaderName::from_bytes(name.as_bytes())
+                .with_context(|| format!("Invalid LLM header name '{}'", name))?;
+            if header_name == AUTHORIZATION || header_name == CONTENT_TYPE {
+                anyhow::bail!("LLM header '{}' cannot be overridden; the client sets it", name);
+            }
+            let header_value = HeaderValue::from_str(value)
+                .with_context(|| format!("Invalid value for LLM header '{}'", name))?;
+            header_map.insert(header_name, header_value);
         }
+        Ok(header_map)
+    }
+    
+    /// Sends a trivial completion to verify the API key and connectivity before serving traffic.
+    pub async fn preflight(&self) -> Result<()> {
+        self.send_chat(&json!({
+            "model": self.model,
+            "messages": [
+                {
+                    "role": "user",
+                    "content": "Reply with OK."
+                }
+            ],
+            "max_tokens": 5,
+            "stream": false
+        })).await?;
+        
+        Ok(())
     }
     
-    pub async fn generate_answer(&self, query: &str, context: &str) -> Result<String> {
+    /// The chat completion request for an answer prompt, with any persona appended to the
+    /// system prompt. Deterministic requests use temperature 0
+    /// and a fixed `seed`; Groq documents seeded sampling as best-effort, so answers can still
+    /// drift if the model or its serving infrastructure changes.
+    fn chat_body(&self, prompt: &str, options: &GenerationOptions) -> serde_json::Value {
+        let mut system_prompt = SYSTEM_PROMPT.to_string();
+        if let Some(persona) = &options.persona {
+            system_prompt.push(' ');
+            system_prompt.push_str(persona);
+        }
+        
+        let mut body = json!({
+            "model": self.model,
+            "messages": [
+                {
+                    "role": "system",
+                    "content": system_prompt
+                },
+                {
+                    "role": "user",
+                    "content": prompt
+                }
+            ],
+            "temperature": 0.1,
+            "max_tokens": 1024,
+            "top_p": 0.9,
+            "stream": false
+        });
+        
+        if options.deterministic {
+            body["temperature"] = json!(0.0);
+            body["seed"] = json!(DETERMINISTIC_SEED);
+        }
+        
+        body
+    }
+    
+    async fn send_chat(&self, body: &serde_json::Value) -> Result<serde_json::Value> {
+        let response = self.client
+            .post(&self.chat_url)
+            .headers(self.headers.clone())
+            .header("Authorization", format!("Bearer {}", self.api_key))
+            .header("Content-Type", "application/json")
+            .json(body)
+            .send()
+            .await?;
+        
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await?;
+            let error = if status == reqwest::StatusCode::UNAUTHORIZED || status == reqwest::StatusCode::FORBIDDEN {
+                GroqError::Unauthorized { status: status.as_u16(), body }
+            } else {
+                GroqError::Api { status: status.as_u16(), body }
+            };
+            return Err(error.into());
+        }
+        
+        Ok(response.json().await?)
+    }
+}
+
+#[async_trait]
+impl LlmProvider for GroqClient {
+    async fn generate_answer(&self, query: &str, context: &str, options: &GenerationOptions) -> Result<LlmAnswer> {
         let prompt = format!(
             "You are a helpful assistant for Freshservice API documentation. \
             Use the following context to answer the user's question. \
@@ -25,47 +174,122 @@ impl GroqClient {
             context, query
         );
         
-        let response = self.client
-            .post("https://api.groq.com/openai/v1/chat/completions")
-            .header("Authorization", format!("Bearer {}", self.api_key))
-            .header("Content-Type", "application/json")
-            .json(&json!({
-                "model": "llama-3.3-70b-versatile",
-                "messages": [
-                    {
-                        "role": "system",
-                        "content": "You are an expert on Freshservice API documentation. Provide accurate, helpful answers based on the given context."
-                    },
-                    {
-                        "role": "user",
-                        "content": prompt
-                    }
-                ],
-                "temperature": 0.1,
-                "max_tokens": 1024,
-                "top_p": 0.9,
-                "stream": false
-            }))
-            .send()
-            .await?;
-        
-        if !response.status().is_success() {
-            let error_text = response.text().await?;
-            return Err(anyhow::anyhow!("Groq API error: {}", error_text));
-        }
-        
-        let response_json: serde_json::Value = response.json().await?;
+        let response_json = self.send_chat(&self.chat_body(&prompt, options)).await?;
         
         let answer = response_json["choices"][0]["message"]["content"]
             .as_str()
             .unwrap_or("Sorry, I couldn't generate an answer.")
             .trim()
             .to_string();
+        let usage = parse_usage(&response_json);
         
         if answer.is_empty() {
-            return Ok("Sorry, I couldn't generate an answer.".to_string());
+            return Ok(LlmAnswer { text: "Sorry, I couldn't generate an answer.".to_string(), usage });
         }
         
-        Ok(answer)
+        Ok(LlmAnswer { text: answer, usage })
+    }
+    
+    fn cacheable(&self) -> bool {
+        true
+    }
+}
+
+/// Reads the OpenAI-style `usage` object from a chat completion response.
+fn parse_usage(response_json: &serde_json::Value) -> Option<TokenUsage> {
+    let usage = response_json.get("usage")?;
+    let prompt = usage["prompt_tokens"].as_u64()?;
+    let completion = usage["completion_tokens"].as_u64()?;
+    Some(TokenUsage {
+        prompt,
+        completion,
+        total: usage["total_tokens"].as_u64().unwrap_or(prompt + completion),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_parse_usage() {
+        let response = json!({
+            "choices": [{"message": {"content": "Use POST /api/v2/tickets"}}],
+            "usage": {"prompt_tokens": 412, "completion_tokens": 58, "total_tokens": 470}
+        });
+        
+        assert_eq!(parse_usage(&response), Some(TokenUsage { prompt: 412, completion: 58, total: 470 }));
+        assert_eq!(parse_usage(&json!({"choices": []})), None);
     }
-}
\ No newline at end of file
+    
+    #[test]
+    fn test_deterministic_body_pins_temperature_and_seed() {
+        let client = GroqClient::new("gsk_test".to_string());
+        
+        let default = client.chat_body("prompt", &GenerationOptions::default());
+        assert_eq!(default["temperature"], json!(0.1));
+        assert!(default.get("seed").is_none());
+        
+        let deterministic = client.chat_body("prompt", &GenerationOptions { deterministic: true, ..Default::default() });
+        assert_eq!(deterministic["temperature"], json!(0.0));
+        assert_eq!(deterministic["seed"], json!(DETERMINISTIC_SEED));
+        assert_eq!(deterministic["messages"][1]["content"], "prompt");
+    }
+    
+    #[tokio::test]
+    async fn test_custom_headers_are_sent_with_completions() {
+        use wiremock::matchers::{header, method};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+        
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(header("authorization", "Bearer gsk_test"))
+            .and(header("x-org-id", "acme"))
+            .and(header("x-route-key", "eu-west"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "choices": [{"message": {"content": "Use POST /api/v2/tickets"}}]
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let headers = HashMap::from([
+            ("X-Org-Id".to_string(), "acme".to_string()),
+            ("X-Route-Key".to_string(), "eu-west".to_string()),
+        ]);
+        let client = GroqClient {
+            chat_url: server.uri(),
+            ..GroqClient::new("gsk_test".to_string()).with_headers(headers).unwrap()
+        };
+        
+        let answer = client.generate_answer("create a ticket", "context", &GenerationOptions::default()).await.unwrap();
+        
+        assert_eq!(answer.text, "Use POST /api/v2/tickets");
+    }
+    
+    #[test]
+    fn test_invalid_headers_are_rejected() {
+        let with_header = |name: &str, value: &str| {
+            GroqClient::new("gsk_test".to_string()).with_headers(HashMap::from([(name.to_string(), value.to_string())]))
+        };
+        
+        assert!(with_header("X-Org-Id", "acme").is_ok());
+        assert!(with_header("X Org", "acme").is_err());
+        assert!(with_header("X-Org-Id", "line\nbreak").is_err());
+        assert!(with_header("authorization", "Bearer other").is_err());
+        assert!(with_header("Content-Type", "text/plain").is_err());
+    }
+    
+    #[test]
+    fn test_persona_is_appended_to_system_prompt() {
+        let client = GroqClient::new("gsk_test".to_string());
+        
+        let neutral = client.chat_body("prompt", &GenerationOptions::default());
+        assert_eq!(neutral["messages"][0]["content"], SYSTEM_PROMPT);
+        
+        let persona = "Answer concisely for a developer.";
+        let body = client.chat_body("prompt", &GenerationOptions { persona: Some(persona.to_string()), ..Default::default() });
+        let system = body["messages"][0]["content"].as_str().unwrap();
+        assert!(system.starts_with(SYSTEM_PROMPT));
+        assert!(system.ends_with(persona));
+    }
+}
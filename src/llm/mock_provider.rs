@@ -0,0 +1,25 @@
+use super::{GenerationOptions, LlmAnswer, LlmProvider};
+use anyhow::Result;
+use async_trait::async_trait;
+
+/// Deterministic provider for tests and demos (`LLM_BACKEND=mock`). Returns the canned answer
+/// when one is set, otherwise echoes the query and context without any network calls.
+pub struct MockLlmProvider {
+    canned_answer: Option<String>,
+}
+
+impl MockLlmProvider {
+    pub fn new(canned_answer: Option<String>) -> Self {
+        Self { canned_answer }
+    }
+}
+
+#[async_trait]
+impl LlmProvider for MockLlmProvider {
+    async fn generate_answer(&self, query: &str, context: &str, _options: &GenerationOptions) -> Result<LlmAnswer> {
+        Ok(LlmAnswer::text(match &self.canned_answer {
+            Some(answer) => answer.clone(),
+            None => format!("Mock answer for '{}' based on:\n{}", query, context.trim()),
+        }))
+    }
+}
@@ -1,3 +1,11 @@
+mod coalescing;
+mod echo_client;
 mod groq_client;
+mod mock_provider;
+mod provider;
 
-pub use groq_client::GroqClient;
\ No newline at end of file
+pub use coalescing::CoalescingProvider;
+pub use echo_client::EchoContextClient;
+pub use groq_client::{GroqClient, GroqError};
+pub use mock_provider::MockLlmProvider;
+pub use provider::{GenerationOptions, LlmAnswer, LlmProvider, TokenUsage};
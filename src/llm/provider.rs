@@ -0,0 +1,47 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use serde::Serialize;
+
+/// Token counts reported by the LLM for a single completion.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct TokenUsage {
+    pub prompt: u64,
+    pub completion: u64,
+    pub total: u64,
+}
+
+/// A generated answer plus the token usage, when the backend reports it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LlmAnswer {
+    pub text: String,
+    pub usage: Option<TokenUsage>,
+}
+
+impl LlmAnswer {
+    /// An answer from a backend that doesn't consume tokens.
+    pub fn text(text: impl Into<String>) -> Self {
+        Self { text: text.into(), usage: None }
+    }
+}
+
+/// Per-request knobs for how the backend samples its answer.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct GenerationOptions {
+    /// Sample greedily with a fixed seed so a given query and context give the same answer
+    /// across runs, as far as the backend honours it.
+    pub deterministic: bool,
+    /// Audience-specific instructions added to the system prompt; `None` keeps the neutral tone.
+    pub persona: Option<String>,
+}
+
+/// A backend capable of turning retrieved documentation context into an answer.
+#[async_trait]
+pub trait LlmProvider: Send + Sync {
+    async fn generate_answer(&self, query: &str, context: &str, options: &GenerationOptions) -> Result<LlmAnswer>;
+
+    /// Whether answers come from a real model and may be persisted in the answer cache. Offline
+    /// and mock backends keep the default, so their output is never served once a model is set up.
+    fn cacheable(&self) -> bool {
+        false
+    }
+}
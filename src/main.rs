@@ -1,3 +1,5 @@
+mod config;
+mod doctor;
 mod scraper;
 mod rag;
 mod llm;
@@ -8,10 +10,16 @@ use anyhow::Result;
 use clap::{Parser, Subcommand};
 use std::path::PathBuf;
 
+/// Exit code of `scrape` when some sections failed but the rest were saved.
+const PARTIAL_SCRAPE_EXIT_CODE: i32 = 3;
+
 #[derive(Parser)]
 #[command(name = "freshservice-rag")]
 #[command(about = "Freshservice API Documentation RAG System")]
 struct Cli {
+    /// Path to a TOML config file (defaults to config/config.toml when present)
+    #[arg(long, global = true)]
+    config: Option<PathBuf>,
     #[command(subcommand)]
     command: Commands,
 }
@@ -22,35 +30,179 @@ enum Commands {
     Scrape {
         #[arg(short, long)]
         output: Option<PathBuf>,
+        /// Only keep endpoints whose path matches one of these globs (repeatable)
+        #[arg(long = "include-path")]
+        include_paths: Vec<String>,
+        /// Drop endpoints whose path matches one of these globs, e.g. '*/tasks*' (repeatable)
+        #[arg(long = "exclude-path")]
+        exclude_paths: Vec<String>,
+        /// Docs section to scrape, e.g. 'ticket' or 'problem' (repeatable; defaults to scrape.sections)
+        #[arg(long = "section")]
+        sections: Vec<String>,
+        /// Write only a sorted 'METHOD /path' list, one per line (defaults to a .txt next to scrape.output)
+        #[arg(long)]
+        endpoints_only: bool,
+        /// Write the JSON on a single line instead of indented (smaller, for machine consumption)
+        #[arg(long, conflicts_with = "endpoints_only")]
+        compact: bool,
+        /// Stop scraping after this many seconds, saving the sections finished so far
+        #[arg(long)]
+        timeout: Option<u64>,
+        /// Retry each section's page fetch this many times after a server error, timeout or connection failure
+        #[arg(long, default_value = "2")]
+        retry: u32,
+    },
+    /// Search the documentation for matching endpoints without calling the LLM
+    Search {
+        query: String,
+        #[arg(short, long, default_value = "10")]
+        limit: usize,
+        /// Scraped documentation file or URL (defaults to scrape.output; scrapes live if missing)
+        #[arg(long)]
+        docs: Option<String>,
     },
     /// Start the web interface
     Serve {
-        #[arg(short, long, default_value = "8080")]
-        port: u16,
+        /// Port to listen on (defaults to server.port from the config)
+        #[arg(short, long)]
+        port: Option<u16>,
+        /// Skip the startup request that verifies the Groq API key
+        #[arg(long)]
+        skip_preflight: bool,
+        /// Re-scrape the documentation on this interval, e.g. 30m or 6h
+        #[arg(long, value_parser = web::parse_interval)]
+        watch: Option<std::time::Duration>,
+        /// Never gzip responses, even for clients that accept it
+        #[arg(long)]
+        no_compression: bool,
+        /// Serve each scraped docs file (*.json) in this directory as a named set instead of scraping
+        #[arg(long)]
+        docs_dir: Option<PathBuf>,
+        /// Serve a scraped docs file or URL (cached per remote_docs) instead of scraping
+        #[arg(long, conflicts_with = "docs_dir")]
+        docs: Option<String>,
+        /// Persist LLM answers in this directory and reuse them for repeated questions until the docs change
+        #[arg(long)]
+        answer_cache_dir: Option<PathBuf>,
+    },
+    /// Check that the docs are reachable, scraping works, the Groq key is valid, and the port is free
+    Doctor {
+        /// Port to check (defaults to server.port from the config)
+        #[arg(short, long)]
+        port: Option<u16>,
     },
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
     let cli = Cli::parse();
+    // Stage timings and other diagnostics are opt-in, e.g. RUST_LOG=freshservice_rag=debug;
+    // warnings go to stderr like the rest of the CLI's warnings
+    tracing_subscriber::fmt()
+        .with_writer(std::io::stderr)
+        .with_env_filter(
+            tracing_subscriber::EnvFilter::try_from_default_env().unwrap_or_else(|_| tracing_subscriber::EnvFilter::new("warn")),
+        )
+        .init();
+    
+    let config = config::Config::load(cli.config.as_deref())?;
+    if let Err(errors) = config.validate() {
+        eprintln!("Invalid configuration:");
+        for error in errors {
+            eprintln!("  - {}", error);
+        }
+        std::process::exit(2);
+    }
 
     match cli.command {
-        Commands::Scrape { output } => {
+        Commands::Scrape { output, include_paths, exclude_paths, sections, endpoints_only, compact, timeout, retry } => {
             println!("Scraping Freshservice API documentation...");
-            let scraper = scraper::FreshserviceScraper::new();
-            let documentation = scraper.scrape_ticket_attributes().await?;
-            
+            let path_filter = scraper::PathFilter::new(&include_paths, &exclude_paths)?;
+            let scraper = scraper::FreshserviceScraper::new()
+                .with_selectors(config.scrape.selectors.clone())
+                .with_min_real_endpoints(config.scrape.min_real_endpoints)
+                .with_path_filter(path_filter)
+                .with_retries(retry);
+            let scraper = match timeout {
+                Some(seconds) => scraper.with_run_timeout(std::time::Duration::from_secs(seconds)),
+                None => scraper,
+            };
+            let sections = if sections.is_empty() { config.scrape.sections.clone() } else { sections };
+            let scrape = scraper.scrape_sections(&sections).await;
+            let failed = scrape.failed.clone();
+            let documentation = scrape.into_documentation()?;
+            if config.scrape.validate_examples {
+                for warning in documentation.validate() {
+                    eprintln!("Warning: {}", warning);
+                }
+            }
             
-            let output_path = output.unwrap_or_else(|| PathBuf::from("data/scraped/documentation.json"));
+            let output_path = match output {
+                Some(path) => path,
+                None if endpoints_only => config.scrape.output.with_extension("txt"),
+                None => config.scrape.output.clone(),
+            };
             if let Some(parent) = output_path.parent() {
                 std::fs::create_dir_all(parent)?;
             }
-            std::fs::write(&output_path, serde_json::to_string_pretty(&documentation)?)?;
-            println!("Documentation saved to: {}", output_path.display());
+            if endpoints_only {
+                let signatures = documentation.endpoint_signatures();
+                std::fs::write(&output_path, signatures.iter().map(|signature| format!("{}\n", signature)).collect::<String>())?;
+                println!("{} endpoint signatures saved to: {}", signatures.len(), output_path.display());
+            } else {
+                std::fs::write(&output_path, documentation.to_json(compact)?)?;
+                println!("Documentation saved to: {}", output_path.display());
+            }
+            
+            if !failed.is_empty() {
+                let retry: Vec<String> = failed.iter().map(|f| format!("--section {}", f.section)).collect();
+                eprintln!("Warning: {} of {} sections failed; the saved documentation is incomplete.", failed.len(), sections.len());
+                eprintln!("Retry them with: freshservice-rag scrape {}", retry.join(" "));
+                std::process::exit(PARTIAL_SCRAPE_EXIT_CODE);
+            }
         }
-        Commands::Serve { port } => {
+        Commands::Search { query, limit, docs } => {
+            let docs_path = docs.as_deref().map_or_else(|| config.scrape.output.clone(), PathBuf::from);
+            let documentation = if let Some(url) = docs.as_deref().filter(|docs| scraper::is_url(docs)) {
+                scraper::load_remote(url, &config).await?
+            } else if docs_path.exists() {
+                models::ScrapedDocumentation::from_file(&docs_path)?
+            } else {
+                println!("{} not found, scraping live documentation...", docs_path.display());
+                scraper::FreshserviceScraper::new()
+                    .with_selectors(config.scrape.selectors.clone())
+                    .with_min_real_endpoints(config.scrape.min_real_endpoints)
+                    .scrape_sections(&config.scrape.sections)
+                    .await
+                    .into_documentation()?
+            };
+            let blocklist = rag::Blocklist::new(&config.compliance.blocked_endpoints)?;
+            let pipeline = rag::RagPipeline::new(documentation)
+                .with_blocklist(&blocklist)
+                .with_tokenizer(rag::tokenizer_for(config.scoring.tokenizer))
+                .with_weights(config.scoring.clone());
+            
+            let results = pipeline.search(&query, limit);
+            if results.is_empty() {
+                println!("No matching endpoints found.");
+            }
+            for (i, result) in results.iter().enumerate() {
+                println!("{}. [{:.2}] {} {} - {}", i + 1, result.score, result.method, result.path, result.name);
+                println!("   {}", result.snippet);
+            }
+        }
+        Commands::Serve { port, skip_preflight, watch, no_compression, docs_dir, docs, answer_cache_dir } => {
+            let port = port.unwrap_or(config.server.port);
             println!("Starting web server on port {}...", port);
-            web::run_server(port).await?;
+            let options = web::ServerOptions { port, skip_preflight, watch, compression: !no_compression, docs_dir, docs, answer_cache_dir };
+            web::run_server(options, config).await?;
+        }
+        Commands::Doctor { port } => {
+            let port = port.unwrap_or(config.server.port);
+            if !doctor::run_doctor(&config, port).await {
+                std::process::exit(1);
+            }
+            println!("All checks passed");
         }
     }
        
@@ -1,27 +1,886 @@
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use crate::rag::EnglishTokenizer;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ApiEndpoint {
     pub name: String,
+    /// Alternative names the docs use for the same endpoint, e.g. "New Ticket" for "Create Ticket".
+    #[serde(default)]
+    pub aliases: Vec<String>,
     pub description: String,
-    pub method: String,
+    pub method: HttpMethod,
     pub path: String,
     pub parameters: Vec<ApiParameter>,
     pub curl_example: Option<String>,
+    /// HTTP status codes the endpoint is documented to return.
+    #[serde(default)]
+    pub status_codes: Vec<u16>,
+    /// Example response body from the docs, parsed as JSON.
+    #[serde(default)]
+    pub response_schema: Option<serde_json::Value>,
+    /// How complete the scraped entry is, from 0.0 to 1.0; low values deserve a manual review.
+    /// Files written before this field existed load as fully trusted.
+    #[serde(default = "full_confidence")]
+    pub confidence: f32,
+    /// When the entry was last confirmed against the live docs.
+    #[serde(default)]
+    pub last_verified: Option<chrono::DateTime<chrono::Utc>>,
+    /// Literal IDs the docs used in the example URL, keyed by the placeholder that replaced
+    /// them (`{"id": "42"}` for `/tickets/42`).
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub sample_ids: BTreeMap<String, String>,
+    /// How a list endpoint pages through results, when its docs say.
+    #[serde(default)]
+    pub pagination: Option<PaginationInfo>,
+    /// Caveats from the docs' note and warning callouts, e.g. "Deleting a ticket is irreversible".
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub notes: Vec<String>,
+    /// Comes from the built-in curated catalog rather than the live docs.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub fallback: bool,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Pagination behaviour documented for a list endpoint.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PaginationInfo {
+    pub style: PaginationStyle,
+    /// Largest `per_page` the API accepts.
+    pub max_per_page: Option<u32>,
+    /// What the docs say about the `Link` response header, verbatim.
+    pub link_header: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PaginationStyle {
+    /// Numbered pages (`page` / `per_page`).
+    #[default]
+    Offset,
+    /// An opaque cursor returned with each page.
+    Cursor,
+}
+
+impl fmt::Display for PaginationInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.style {
+            PaginationStyle::Offset => f.write_str("page-based (`page` and `per_page` query parameters)")?,
+            PaginationStyle::Cursor => f.write_str("cursor-based (pass the cursor from the previous page)")?,
+        }
+        if let Some(max) = self.max_per_page {
+            write!(f, ", at most {} per page", max)?;
+        }
+        if let Some(note) = &self.link_header {
+            write!(f, ". {}", note)?;
+        }
+        Ok(())
+    }
+}
+
+fn full_confidence() -> f32 {
+    1.0
+}
+
+/// Matches an endpoint loaded from JSON without the optional fields: in particular, fully
+/// trusted rather than `confidence: 0.0`.
+impl Default for ApiEndpoint {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            aliases: Vec::new(),
+            description: String::new(),
+            method: HttpMethod::default(),
+            path: String::new(),
+            parameters: Vec::new(),
+            curl_example: None,
+            status_codes: Vec::new(),
+            response_schema: None,
+            confidence: full_confidence(),
+            last_verified: None,
+            sample_ids: BTreeMap::new(),
+            pagination: None,
+            notes: Vec::new(),
+            fallback: false,
+        }
+    }
+}
+
+/// A problem found by [`ApiEndpoint::validate_payload`]. `field` is a dotted path for nested
+/// attributes, e.g. `custom_fields.priority_reason`.
+#[derive(Debug, Clone, PartialEq, Serialize, thiserror::Error)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ValidationError {
+    #[error("request body must be a JSON object")]
+    NotAnObject,
+    #[error("missing required parameter '{field}'")]
+    MissingRequired { field: String },
+    #[error("parameter '{field}' should be {expected}, got {found}")]
+    TypeMismatch { field: String, expected: ParamType, found: &'static str },
+}
+
+impl ApiEndpoint {
+    /// Checks a would-be request body against the documented parameters: required parameters
+    /// must be present and non-null, and known parameters must have the documented JSON type.
+    /// Unknown fields are allowed. Returns every problem found; empty means valid.
+    pub fn validate_payload(&self, payload: &serde_json::Value) -> Vec<ValidationError> {
+        let mut errors = Vec::new();
+        match payload.as_object() {
+            Some(object) => validate_object(&self.parameters, object, "", &mut errors),
+            None => errors.push(ValidationError::NotAnObject),
+        }
+        errors
+    }
+    
+    /// The JSON body the curl example sends with `-d` / `--data`, if it has a parseable one.
+    pub fn example_payload(&self) -> Option<serde_json::Value> {
+        let curl = self.curl_example.as_deref()?;
+        let body = regex::Regex::new(r"(?:-d|--data(?:-raw)?)\s+'([^']*)'").ok()?
+            .captures(curl)?
+            .get(1)?
+            .as_str();
+        serde_json::from_str(body).ok()
+    }
+    
+    /// Where the curl example's body disagrees with the parameter table: fields the table does
+    /// not document, and values of a different type than documented. Missing fields are fine,
+    /// since examples rarely set every optional one. Empty when there is nothing to compare.
+    pub fn example_mismatches(&self) -> Vec<String> {
+        let Some(payload) = self.example_payload().filter(|_| !self.parameters.is_empty()) else {
+            return Vec::new();
+        };
+        let Some(object) = payload.as_object() else {
+            return vec![ValidationError::NotAnObject.to_string()];
+        };
+        
+        let mut mismatches = Vec::new();
+        undocumented_fields(&self.parameters, object, "", &mut mismatches);
+        mismatches.extend(
+            self.validate_payload(&payload)
+                .into_iter()
+                .filter(|error| matches!(error, ValidationError::TypeMismatch { .. }))
+                .map(|error| error.to_string()),
+        );
+        mismatches
+    }
+    
+    /// A GET on a collection rather than a single resource.
+    pub fn is_list(&self) -> bool {
+        self.method == HttpMethod::Get && !self.path.ends_with('}')
+    }
+    
+    /// Replaces literal numeric IDs in `path` and the curl example with placeholders, recording
+    /// each literal in `sample_ids`. The first ID becomes `{id}`; later ones, including any after
+    /// an already templated segment, are named after the collection they follow, so
+    /// `/tickets/1/time_entries/7` becomes `/tickets/{id}/time_entries/{time_entry_id}`.
+    pub fn parameterize_ids(&mut self) {
+        let mut segments: Vec<String> = Vec::new();
+        for segment in self.path.split('/') {
+            let is_id = !segment.is_empty() && segment.chars().all(|c| c.is_ascii_digit());
+            if !is_id {
+                segments.push(segment.to_string());
+                continue;
+            }
+            let after_id = segments.iter().any(|segment| segment.starts_with('{') && segment.ends_with('}'));
+            let placeholder = match segments.last() {
+                Some(collection) if after_id => format!("{}_id", EnglishTokenizer::stem(collection)),
+                _ => "id".to_string(),
+            };
+            self.sample_ids.insert(placeholder.clone(), segment.to_string());
+            segments.push(format!("{{{}}}", placeholder));
+        }
+        
+        let templated = segments.join("/");
+        if templated == self.path {
+            return;
+        }
+        if let Some(curl) = &self.curl_example {
+            self.curl_example = Some(curl.replace(&self.path, &templated));
+        }
+        self.path = templated;
+    }
+}
+
+fn validate_object(
+    parameters: &[ApiParameter],
+    object: &serde_json::Map<String, serde_json::Value>,
+    prefix: &str,
+    errors: &mut Vec<ValidationError>,
+) {
+    for param in parameters {
+        let field = format!("{}{}", prefix, param.name);
+        let value = match object.get(&param.name) {
+            Some(value) if !value.is_null() => value,
+            _ => {
+                if param.required {
+                    errors.push(ValidationError::MissingRequired { field });
+                }
+                continue;
+            }
+        };
+
+        if !param.param_type.accepts(value) {
+            errors.push(ValidationError::TypeMismatch {
+                field,
+                expected: param.param_type.clone(),
+                found: json_type_name(value),
+            });
+        } else if let Some(nested) = value.as_object()
+            && !param.children.is_empty()
+        {
+            validate_object(&param.children, nested, &format!("{}.", field), errors);
+        }
+    }
+}
+
+fn undocumented_fields(
+    parameters: &[ApiParameter],
+    object: &serde_json::Map<String, serde_json::Value>,
+    prefix: &str,
+    mismatches: &mut Vec<String>,
+) {
+    for (key, value) in object {
+        let field = format!("{}{}", prefix, key);
+        match parameters.iter().find(|param| &param.name == key) {
+            None => mismatches.push(format!("field '{}' is not a documented parameter", field)),
+            Some(param) => {
+                if let Some(nested) = value.as_object()
+                    && !param.children.is_empty()
+                {
+                    undocumented_fields(&param.children, nested, &format!("{}.", field), mismatches);
+                }
+            }
+        }
+    }
+}
+
+/// JSON type of `value`, using the same names as [`ParamType`].
+pub fn json_type_name(value: &serde_json::Value) -> &'static str {
+    match value {
+        serde_json::Value::Null => "null",
+        serde_json::Value::Bool(_) => "boolean",
+        serde_json::Value::Number(n) if n.is_f64() => "number",
+        serde_json::Value::Number(_) => "integer",
+        serde_json::Value::String(_) => "string",
+        serde_json::Value::Array(_) => "array",
+        serde_json::Value::Object(_) => "object",
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ApiParameter {
     pub name: String,
-    pub param_type: String,
+    pub param_type: ParamType,
     pub description: String,
     pub required: bool,
     pub default: Option<String>,
+    /// Nested attributes of an `object` parameter, e.g. the entries of `custom_fields`.
+    #[serde(default)]
+    pub children: Vec<ApiParameter>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Parameter type parsed from the scraped docs. Serialized as a plain lowercase string so
+/// documentation files written before this enum existed still load.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum ParamType {
+    #[default]
+    String,
+    Integer,
+    Number,
+    Boolean,
+    Array,
+    Object,
+    /// Type text the parser doesn't recognize, kept verbatim.
+    Unknown(String),
+}
+
+impl ParamType {
+    pub fn parse(text: &str) -> Self {
+        let normalized = text.trim().to_lowercase();
+        match normalized.as_str() {
+            "string" | "str" | "text" => ParamType::String,
+            "integer" | "int" | "long" => ParamType::Integer,
+            "number" | "float" | "double" | "decimal" => ParamType::Number,
+            "boolean" | "bool" => ParamType::Boolean,
+            "object" | "hash" | "dict" | "map" | "json" => ParamType::Object,
+            t if t == "array" || t.starts_with("array of") || t.starts_with("array[") => ParamType::Array,
+            _ => ParamType::Unknown(text.trim().to_string()),
+        }
+    }
+    
+    /// Whether a JSON value has this type. Unrecognized types accept anything.
+    pub fn accepts(&self, value: &serde_json::Value) -> bool {
+        match self {
+            ParamType::String => value.is_string(),
+            ParamType::Integer => value.is_i64() || value.is_u64(),
+            ParamType::Number => value.is_number(),
+            ParamType::Boolean => value.is_boolean(),
+            ParamType::Array => value.is_array(),
+            ParamType::Object => value.is_object(),
+            ParamType::Unknown(_) => true,
+        }
+    }
+    
+    pub fn as_str(&self) -> &str {
+        match self {
+            ParamType::String => "string",
+            ParamType::Integer => "integer",
+            ParamType::Number => "number",
+            ParamType::Boolean => "boolean",
+            ParamType::Array => "array",
+            ParamType::Object => "object",
+            ParamType::Unknown(text) => text,
+        }
+    }
+}
+
+impl fmt::Display for ParamType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Serialize for ParamType {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for ParamType {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        Ok(ParamType::parse(&text))
+    }
+}
+
+/// HTTP method of an endpoint, always canonical uppercase so method scoring and filtering
+/// can compare reliably. Variants are declared, and therefore ordered, in the canonical
+/// GET, POST, PUT, PATCH, DELETE order used to rank otherwise tied endpoints.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum HttpMethod {
+    #[default]
+    Get,
+    Post,
+    Put,
+    Patch,
+    Delete,
+    Head,
+    Options,
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("unsupported HTTP method '{0}'")]
+pub struct UnknownMethod(pub String);
+
+impl HttpMethod {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HttpMethod::Get => "GET",
+            HttpMethod::Post => "POST",
+            HttpMethod::Put => "PUT",
+            HttpMethod::Patch => "PATCH",
+            HttpMethod::Delete => "DELETE",
+            HttpMethod::Head => "HEAD",
+            HttpMethod::Options => "OPTIONS",
+        }
+    }
+}
+
+impl FromStr for HttpMethod {
+    type Err = UnknownMethod;
+    
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        match text.trim().to_ascii_uppercase().as_str() {
+            "GET" => Ok(HttpMethod::Get),
+            "POST" => Ok(HttpMethod::Post),
+            "PUT" => Ok(HttpMethod::Put),
+            "PATCH" => Ok(HttpMethod::Patch),
+            "DELETE" => Ok(HttpMethod::Delete),
+            "HEAD" => Ok(HttpMethod::Head),
+            "OPTIONS" => Ok(HttpMethod::Options),
+            _ => Err(UnknownMethod(text.trim().to_string())),
+        }
+    }
+}
+
+impl fmt::Display for HttpMethod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Serialize for HttpMethod {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for HttpMethod {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        text.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ScrapedDocumentation {
     pub base_url: String,
+    #[serde(deserialize_with = "deserialize_endpoints")]
     pub endpoints: Vec<ApiEndpoint>,
     pub scraped_at: chrono::DateTime<chrono::Utc>,
-}
\ No newline at end of file
+    #[serde(default)]
+    pub api_version: Option<String>,
+    #[serde(default)]
+    pub changelog: Vec<ChangelogEntry>,
+    /// Sections whose page looked like an empty JavaScript shell rather than rendered docs, so
+    /// their endpoints are likely missing or came from the curated fallback.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub js_rendered_sections: Vec<String>,
+}
+
+/// Loads each endpoint on its own, skipping (with a warning) any that don't parse, e.g. one
+/// with an unsupported HTTP method, so a single bad entry doesn't reject the whole catalog.
+fn deserialize_endpoints<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<ApiEndpoint>, D::Error> {
+    let entries = Vec::<serde_json::Value>::deserialize(deserializer)?;
+    Ok(entries
+        .into_iter()
+        .filter_map(|entry| {
+            let name = entry.get("name").and_then(|name| name.as_str()).unwrap_or("<unnamed>").to_string();
+            serde_json::from_value(entry)
+                .inspect_err(|e| tracing::warn!(endpoint = %name, error = %e, "skipping endpoint that failed to load"))
+                .ok()
+        })
+        .collect())
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ChangelogEntry {
+    pub version: Option<String>,
+    pub date: Option<String>,
+    pub description: String,
+}
+
+/// Placeholder hosts the docs (and LLM answers derived from them) use in example URLs, matched
+/// only as a whole host: right after `://` or an opening quote.
+const PLACEHOLDER_HOST_PATTERN: &str = r#"(://|['"])(?:yourdomain|domain)\.freshservice\.com\b"#;
+
+/// Replaces placeholder Freshservice hosts in `text` with the user's real `domain`. Real hosts
+/// that merely end in "domain", like `mydomain.freshservice.com`, are left alone.
+pub fn apply_domain(text: &str, domain: &str) -> String {
+    let Ok(re) = regex::Regex::new(PLACEHOLDER_HOST_PATTERN) else {
+        return text.to_string();
+    };
+    re.replace_all(text, |captures: &regex::Captures| format!("{}{}", &captures[1], domain))
+        .into_owned()
+}
+
+impl ScrapedDocumentation {
+    /// `METHOD /path` for every endpoint, sorted and without duplicates.
+    pub fn endpoint_signatures(&self) -> Vec<String> {
+        let mut signatures: Vec<String> = self.endpoints
+            .iter()
+            .map(|endpoint| format!("{} {}", endpoint.method, endpoint.path))
+            .collect();
+        signatures.sort();
+        signatures.dedup();
+        signatures
+    }
+    
+    /// Rewrites stored curl examples to target `domain` so they are copy-paste runnable.
+    pub fn with_domain(mut self, domain: &str) -> Self {
+        for endpoint in &mut self.endpoints {
+            if let Some(curl) = &endpoint.curl_example {
+                endpoint.curl_example = Some(apply_domain(curl, domain));
+            }
+        }
+        self
+    }
+    
+    pub fn from_file(path: &std::path::Path) -> anyhow::Result<Self> {
+        use anyhow::Context;
+        
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read documentation file {}", path.display()))?;
+        serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse documentation file {}", path.display()))
+    }
+    
+    /// Consistency warnings about the scraped data: curl example bodies that send undocumented
+    /// fields or values of the wrong type, which usually means the parameter table or the
+    /// example was extracted wrongly.
+    pub fn validate(&self) -> Vec<String> {
+        self.endpoints
+            .iter()
+            .flat_map(|endpoint| {
+                endpoint.example_mismatches().into_iter().map(move |mismatch| {
+                    format!("{} ({} {}): curl example {}", endpoint.name, endpoint.method, endpoint.path, mismatch)
+                })
+            })
+            .collect()
+    }
+    
+    /// The catalog as JSON: indented for people to read, or on one line when `compact`, which
+    /// is much smaller for large scrapes. Both load back with [`Self::from_file`].
+    pub fn to_json(&self, compact: bool) -> serde_json::Result<String> {
+        if compact {
+            serde_json::to_string(self)
+        } else {
+            serde_json::to_string_pretty(self)
+        }
+    }
+    
+    /// Stable fingerprint of the catalog's content, used for HTTP ETags and cache invalidation.
+    /// Scrape and verification timestamps are left out, so re-scraping unchanged docs keeps the
+    /// same hash across reloads and restarts.
+    pub fn content_hash(&self) -> String {
+        let endpoints: Vec<ApiEndpoint> = self.endpoints
+            .iter()
+            .map(|endpoint| ApiEndpoint { last_verified: None, ..endpoint.clone() })
+            .collect();
+        let content = serde_json::to_string(&(&self.base_url, &self.api_version, &self.changelog, &endpoints))
+            .unwrap_or_default();
+        format!("{:016x}", stable_hash(content.as_bytes()))
+    }
+}
+
+/// 64-bit FNV-1a of `bytes`. Unlike `DefaultHasher`, its output is fixed, so hashes written to
+/// disk or sent as ETags stay valid across Rust releases.
+pub fn stable_hash(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+/// Fixtures shared by test modules across the crate.
+#[cfg(test)]
+impl ApiEndpoint {
+    /// An endpoint with only the fields ranking and filtering look at.
+    pub fn sample(name: &str, method: HttpMethod, path: &str, description: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            method,
+            path: path.to_string(),
+            description: description.to_string(),
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+impl ScrapedDocumentation {
+    /// Documentation with one [`ApiEndpoint::sample`] per `(name, method, path, description)`.
+    pub fn sample(endpoints: &[(&str, HttpMethod, &str, &str)]) -> Self {
+        Self {
+            endpoints: endpoints
+                .iter()
+                .map(|&(name, method, path, description)| ApiEndpoint::sample(name, method, path, description))
+                .collect(),
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_param_type_parse() {
+        assert_eq!(ParamType::parse("Integer"), ParamType::Integer);
+        assert_eq!(ParamType::parse(" number "), ParamType::Number);
+        assert_eq!(ParamType::parse("array of strings"), ParamType::Array);
+        assert_eq!(ParamType::parse("hash"), ParamType::Object);
+        assert_eq!(ParamType::parse("bool"), ParamType::Boolean);
+        assert_eq!(ParamType::parse("datetime"), ParamType::Unknown("datetime".to_string()));
+    }
+    
+    #[test]
+    fn test_param_type_serde_is_string_compatible() {
+        let param: ApiParameter = serde_json::from_str(
+            r#"{"name": "ids", "param_type": "array", "description": "", "required": false, "default": null}"#,
+        ).unwrap();
+        assert_eq!(param.param_type, ParamType::Array);
+        
+        let unknown: ApiParameter = serde_json::from_str(
+            r#"{"name": "due_by", "param_type": "datetime", "description": "", "required": false, "default": null}"#,
+        ).unwrap();
+        let json = serde_json::to_value(&unknown).unwrap();
+        assert_eq!(json["param_type"], "datetime");
+    }
+    
+    #[test]
+    fn test_http_method_canonicalizes_case() {
+        assert_eq!("Get".parse::<HttpMethod>().unwrap(), HttpMethod::Get);
+        assert_eq!(" delete ".parse::<HttpMethod>().unwrap(), HttpMethod::Delete);
+        assert_eq!("PaTcH".parse::<HttpMethod>().unwrap().to_string(), "PATCH");
+        
+        let endpoint: ApiEndpoint = serde_json::from_str(
+            r#"{"name":"x","description":"","method":"post","path":"/api/v2/tickets","parameters":[],"curl_example":null}"#
+        ).unwrap();
+        assert_eq!(endpoint.method, HttpMethod::Post);
+        assert_eq!(serde_json::to_value(&endpoint).unwrap()["method"], "POST");
+    }
+    
+    #[test]
+    fn test_http_method_rejects_unknown() {
+        let err = "POSTs".parse::<HttpMethod>().unwrap_err();
+        assert_eq!(err.to_string(), "unsupported HTTP method 'POSTs'");
+        assert!("".parse::<HttpMethod>().is_err());
+        assert!(serde_json::from_str::<HttpMethod>(r#""FETCH""#).is_err());
+    }
+    
+    #[test]
+    fn test_unknown_method_skips_only_that_endpoint() {
+        let documentation: ScrapedDocumentation = serde_json::from_str(r#"{
+            "base_url": "https://api.freshservice.com",
+            "scraped_at": "2024-01-01T00:00:00Z",
+            "endpoints": [
+                {"name":"Fetch Ticket","description":"","method":"FETCH","path":"/api/v2/tickets","parameters":[],"curl_example":null},
+                {"name":"Create Ticket","description":"","method":"POST","path":"/api/v2/tickets","parameters":[],"curl_example":null}
+            ]
+        }"#).unwrap();
+        
+        assert_eq!(documentation.endpoints.len(), 1);
+        assert_eq!(documentation.endpoints[0].name, "Create Ticket");
+    }
+    
+    fn ticket_endpoint() -> ApiEndpoint {
+        let param = |name: &str, param_type: ParamType, required: bool| ApiParameter {
+            name: name.to_string(),
+            param_type,
+            required,
+            ..Default::default()
+        };
+        ApiEndpoint {
+            name: "Create Ticket".to_string(),
+            parameters: vec![
+                param("email", ParamType::String, true),
+                param("priority", ParamType::Integer, true),
+                param("cc_emails", ParamType::Array, false),
+                ApiParameter {
+                    children: vec![param("impact_reason", ParamType::String, true)],
+                    ..param("custom_fields", ParamType::Object, false)
+                },
+            ],
+            ..Default::default()
+        }
+    }
+    
+    #[test]
+    fn test_validate_payload_accepts_valid_body() {
+        let payload = serde_json::json!({
+            "email": "a@b.com",
+            "priority": 2,
+            "custom_fields": {"impact_reason": "outage"},
+            "unknown_extra": true
+        });
+        
+        assert!(ticket_endpoint().validate_payload(&payload).is_empty());
+    }
+    
+    #[test]
+    fn test_validate_payload_reports_missing_required() {
+        let payload = serde_json::json!({"email": "a@b.com", "custom_fields": {}});
+        
+        assert_eq!(
+            ticket_endpoint().validate_payload(&payload),
+            vec![
+                ValidationError::MissingRequired { field: "priority".to_string() },
+                ValidationError::MissingRequired { field: "custom_fields.impact_reason".to_string() },
+            ]
+        );
+    }
+    
+    #[test]
+    fn test_validate_flags_curl_example_disagreeing_with_parameters() {
+        let mut endpoint = ticket_endpoint();
+        endpoint.method = HttpMethod::Post;
+        endpoint.path = "/api/v2/tickets".to_string();
+        endpoint.curl_example = Some(
+            r#"curl -u api_key:X -H "Content-Type: application/json" -d '{"email": "tom@outerspace.com", "priority": 1, "subject": "VPN down", "custom_fields": {"impact_reason": 5}}' -X POST 'https://domain.freshservice.com/api/v2/tickets'"#.to_string(),
+        );
+        let docs = ScrapedDocumentation { endpoints: vec![endpoint.clone()], ..Default::default() };
+        
+        assert_eq!(
+            docs.validate(),
+            vec![
+                "Create Ticket (POST /api/v2/tickets): curl example field 'subject' is not a documented parameter",
+                "Create Ticket (POST /api/v2/tickets): curl example parameter 'custom_fields.impact_reason' should be string, got integer",
+            ]
+        );
+        
+        // An example that agrees with the table, or one without a body, is fine
+        endpoint.curl_example = Some(r#"curl -d '{"email": "tom@outerspace.com"}' -X POST 'https://domain.freshservice.com/api/v2/tickets'"#.to_string());
+        assert!(endpoint.example_mismatches().is_empty());
+        endpoint.curl_example = Some("curl -X GET 'https://domain.freshservice.com/api/v2/tickets'".to_string());
+        assert!(endpoint.example_payload().is_none());
+    }
+    
+    #[test]
+    fn test_validate_payload_reports_wrong_types() {
+        let payload = serde_json::json!({"email": 42, "priority": "high", "cc_emails": "x@y.com"});
+        let errors = ticket_endpoint().validate_payload(&payload);
+        
+        assert_eq!(errors.len(), 3);
+        assert_eq!(errors[1].to_string(), "parameter 'priority' should be integer, got string");
+        assert_eq!(
+            serde_json::to_value(&errors[0]).unwrap(),
+            serde_json::json!({"kind": "type_mismatch", "field": "email", "expected": "string", "found": "integer"})
+        );
+        assert_eq!(ticket_endpoint().validate_payload(&serde_json::json!([])), vec![ValidationError::NotAnObject]);
+    }
+    
+    #[test]
+    fn test_parameterize_ids_templates_path_and_curl() {
+        let mut endpoint = ApiEndpoint {
+            method: HttpMethod::Get,
+            path: "/api/v2/tickets/42".to_string(),
+            curl_example: Some("curl -u api_key:X -X GET 'https://domain.freshservice.com/api/v2/tickets/42'".to_string()),
+            ..Default::default()
+        };
+        
+        endpoint.parameterize_ids();
+        
+        assert_eq!(endpoint.path, "/api/v2/tickets/{id}");
+        assert_eq!(endpoint.curl_example.as_deref(), Some("curl -u api_key:X -X GET 'https://domain.freshservice.com/api/v2/tickets/{id}'"));
+        assert_eq!(endpoint.sample_ids, BTreeMap::from([("id".to_string(), "42".to_string())]));
+    }
+    
+    #[test]
+    fn test_parameterize_ids_names_nested_ids() {
+        let mut endpoint = ApiEndpoint { path: "/api/v2/tickets/1/conversations/7".to_string(), ..Default::default() };
+        endpoint.parameterize_ids();
+        assert_eq!(endpoint.path, "/api/v2/tickets/{id}/conversations/{conversation_id}");
+        assert_eq!(endpoint.sample_ids["conversation_id"], "7");
+        
+        for (path, templated) in [
+            ("/api/v2/tickets/1/time_entries/5", "/api/v2/tickets/{id}/time_entries/{time_entry_id}"),
+            ("/api/v2/problems/2/statuses/3", "/api/v2/problems/{id}/statuses/{status_id}"),
+        ] {
+            let mut endpoint = ApiEndpoint { path: path.to_string(), ..Default::default() };
+            endpoint.parameterize_ids();
+            assert_eq!(endpoint.path, templated);
+        }
+        
+        let mut collection = ApiEndpoint { path: "/api/v2/tickets".to_string(), ..Default::default() };
+        collection.parameterize_ids();
+        assert_eq!(collection.path, "/api/v2/tickets");
+        assert!(collection.sample_ids.is_empty());
+    }
+    
+    #[test]
+    fn test_parameterize_ids_after_templated_segment() {
+        let mut endpoint = ApiEndpoint { path: "/api/v2/tickets/{id}/notes/7".to_string(), ..Default::default() };
+        endpoint.parameterize_ids();
+        assert_eq!(endpoint.path, "/api/v2/tickets/{id}/notes/{note_id}");
+        assert_eq!(endpoint.sample_ids, BTreeMap::from([("note_id".to_string(), "7".to_string())]));
+    }
+    
+    #[test]
+    fn test_endpoint_signatures_are_sorted_and_unique() {
+        let endpoint = |method, path: &str| ApiEndpoint { method, path: path.to_string(), ..Default::default() };
+        let documentation = ScrapedDocumentation {
+            endpoints: vec![
+                endpoint(HttpMethod::Put, "/api/v2/tickets/{id}"),
+                endpoint(HttpMethod::Get, "/api/v2/tickets"),
+                endpoint(HttpMethod::Delete, "/api/v2/tickets/{id}"),
+                endpoint(HttpMethod::Get, "/api/v2/tickets"),
+            ],
+            ..Default::default()
+        };
+        
+        assert_eq!(documentation.endpoint_signatures(), vec![
+            "DELETE /api/v2/tickets/{id}",
+            "GET /api/v2/tickets",
+            "PUT /api/v2/tickets/{id}",
+        ]);
+    }
+    
+    #[test]
+    fn test_default_endpoint_matches_loaded_one() {
+        let loaded: ApiEndpoint = serde_json::from_value(serde_json::json!({
+            "name": "Create Ticket",
+            "description": "",
+            "method": "POST",
+            "path": "/api/v2/tickets",
+            "parameters": [],
+            "curl_example": null
+        })).unwrap();
+        
+        assert_eq!(loaded.confidence, 1.0);
+        assert_eq!(ApiEndpoint::default().confidence, loaded.confidence);
+    }
+    
+    #[test]
+    fn test_content_hash_ignores_timestamps() {
+        let docs = |scraped_at: i64| ScrapedDocumentation {
+            base_url: "https://api.freshservice.com".to_string(),
+            scraped_at: chrono::DateTime::from_timestamp(scraped_at, 0).unwrap(),
+            endpoints: vec![ApiEndpoint {
+                name: "Create Ticket".to_string(),
+                last_verified: chrono::DateTime::from_timestamp(scraped_at, 0),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        
+        assert_eq!(docs(1_700_000_000).content_hash(), docs(1_800_000_000).content_hash());
+        let mut renamed = docs(1_700_000_000);
+        renamed.endpoints[0].name = "Create a Ticket".to_string();
+        assert_ne!(renamed.content_hash(), docs(1_700_000_000).content_hash());
+        assert_eq!(stable_hash(b"a"), 0xaf63_dc4c_8601_ec8c);
+    }
+    
+    #[test]
+    fn test_apply_domain() {
+        let answer = "Run:\ncurl -u key:X 'https://domain.freshservice.com/api/v2/tickets'\nor https://yourdomain.freshservice.com/api/v2/agents";
+        
+        assert_eq!(
+            apply_domain(answer, "acme.freshservice.com"),
+            "Run:\ncurl -u key:X 'https://acme.freshservice.com/api/v2/tickets'\nor https://acme.freshservice.com/api/v2/agents"
+        );
+        assert_eq!(apply_domain("no urls here", "acme.freshservice.com"), "no urls here");
+        let real_host = "curl 'https://mydomain.freshservice.com/api/v2/tickets'";
+        assert_eq!(apply_domain(real_host, "mydomain.freshservice.com"), real_host);
+    }
+    
+    #[test]
+    fn test_with_domain_rewrites_curl_examples() {
+        let docs = ScrapedDocumentation {
+            endpoints: vec![ApiEndpoint {
+                curl_example: Some("curl 'https://domain.freshservice.com/api/v2/tickets'".to_string()),
+                ..Default::default()
+            }],
+            ..Default::default()
+        }.with_domain("acme.freshservice.com");
+        
+        assert_eq!(docs.endpoints[0].curl_example.as_deref(), Some("curl 'https://acme.freshservice.com/api/v2/tickets'"));
+    }
+    
+    #[test]
+    fn test_compact_json_round_trips() {
+        let docs = ScrapedDocumentation {
+            base_url: "https://api.freshservice.com".to_string(),
+            endpoints: vec![ApiEndpoint {
+                name: "Create Ticket".to_string(),
+                method: HttpMethod::Post,
+                path: "/api/v2/tickets".to_string(),
+                ..Default::default()
+            }],
+            api_version: Some("v2".to_string()),
+            ..Default::default()
+        };
+        let compact = docs.to_json(true).unwrap();
+        let pretty = docs.to_json(false).unwrap();
+        assert!(!compact.contains('\n'));
+        assert!(compact.len() < pretty.len());
+        
+        let path = std::env::temp_dir().join(format!("freshservice-rag-compact-{}.json", std::process::id()));
+        std::fs::write(&path, &compact).unwrap();
+        let loaded = ScrapedDocumentation::from_file(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        
+        assert_eq!(loaded.to_json(false).unwrap(), pretty);
+    }
+}
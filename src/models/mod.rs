@@ -1,3 +1,3 @@
 mod api_docs;
 
-pub use api_docs::{ApiEndpoint, ApiParameter, ScrapedDocumentation};
\ No newline at end of file
+pub use api_docs::{apply_domain, json_type_name, ApiEndpoint, ApiParameter, ChangelogEntry, HttpMethod, PaginationInfo, PaginationStyle, ParamType, ScrapedDocumentation, stable_hash};
@@ -0,0 +1,78 @@
+use crate::models::{ApiEndpoint, HttpMethod};
+use anyhow::{Context, Result};
+use glob::Pattern;
+
+/// Endpoints a deployment must never surface. Each entry is an endpoint name or alias
+/// (case-insensitive) or a path glob, optionally prefixed with an uppercase method: `Delete Ticket`,
+/// `/api/v2/tickets/*/tasks*`, `DELETE *`. Only an uppercase first word counts as a method, so
+/// names that start with a verb, like `Delete a Ticket`, stay whole.
+#[derive(Debug, Clone, Default)]
+pub struct Blocklist {
+    rules: Vec<BlockRule>,
+}
+
+#[derive(Debug, Clone)]
+struct BlockRule {
+    method: Option<HttpMethod>,
+    name: String,
+    path: Pattern,
+}
+
+impl Blocklist {
+    pub fn new(entries: &[String]) -> Result<Self> {
+        let rules = entries.iter()
+            .map(|entry| {
+                let entry = entry.trim();
+                let (method, target) = match entry.split_once(' ') {
+                    Some((method, rest)) if !rest.trim().is_empty() && method == method.to_uppercase() => match method.parse() {
+                        Ok(method) => (Some(method), rest.trim()),
+                        Err(_) => (None, entry),
+                    },
+                    _ => (None, entry),
+                };
+                let path = Pattern::new(target)
+                    .with_context(|| format!("Invalid blocked endpoint pattern '{}'", entry))?;
+                Ok(BlockRule { method, name: target.to_string(), path })
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self { rules })
+    }
+
+    pub fn blocks(&self, endpoint: &ApiEndpoint) -> bool {
+        self.rules.iter().any(|rule| {
+            rule.method.is_none_or(|method| method == endpoint.method)
+                && (rule.path.matches(&endpoint.path)
+                    || std::iter::once(&endpoint.name)
+                        .chain(&endpoint.aliases)
+                        .any(|name| name.eq_ignore_ascii_case(&rule.name)))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_block_by_name_path_and_method() {
+        let blocklist = Blocklist::new(&[
+            "create ticket task".to_string(),
+            "/api/v2/tickets/*/time_entries*".to_string(),
+            "DELETE *".to_string(),
+        ]).unwrap();
+
+        assert!(blocklist.blocks(&ApiEndpoint::sample("Create Ticket Task", HttpMethod::Post, "/api/v2/tickets/{id}/tasks", "")));
+        assert!(blocklist.blocks(&ApiEndpoint::sample("View Time Entries", HttpMethod::Get, "/api/v2/tickets/{id}/time_entries", "")));
+        assert!(blocklist.blocks(&ApiEndpoint::sample("Delete a Ticket", HttpMethod::Delete, "/api/v2/tickets/{id}", "")));
+        assert!(!blocklist.blocks(&ApiEndpoint::sample("View a Ticket", HttpMethod::Get, "/api/v2/tickets/{id}", "")));
+        assert!(!Blocklist::default().blocks(&ApiEndpoint::sample("Delete a Ticket", HttpMethod::Delete, "/api/v2/tickets/{id}", "")));
+    }
+
+    #[test]
+    fn test_name_starting_with_a_method_word_is_kept_whole() {
+        let blocklist = Blocklist::new(&["Delete a Ticket".to_string()]).unwrap();
+
+        assert!(blocklist.blocks(&ApiEndpoint::sample("Delete a Ticket", HttpMethod::Delete, "/api/v2/tickets/{id}", "")));
+        assert!(!blocklist.blocks(&ApiEndpoint::sample("View a Ticket", HttpMethod::Delete, "/api/v2/tickets/{id}/view", "")));
+    }
+}
@@ -0,0 +1,227 @@
+use super::{EnglishTokenizer, Tokenizer};
+use anyhow::{Context, Result};
+use crate::models::ApiEndpoint;
+use serde::{Deserialize, Serialize};
+use crate::models::stable_hash;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Turns text into a vector whose cosine similarity to another text's vector reflects how
+/// related the two are.
+pub trait Embedder: Send + Sync {
+    fn embed(&self, text: &str) -> Vec<f32>;
+
+    /// Names the embedder and its settings; vectors from embedders with different ids are not
+    /// comparable, so a cache built by another one is discarded.
+    fn id(&self) -> String;
+}
+
+/// Offline embedder: tokenizer terms hashed into a fixed number of buckets, L2-normalized.
+/// Needs no model or network, and catches word overlap the substring signals miss (e.g. terms
+/// spread across name, description and parameters).
+#[derive(Debug, Clone, Copy)]
+pub struct HashingEmbedder {
+    dimensions: usize,
+}
+
+impl Default for HashingEmbedder {
+    fn default() -> Self {
+        Self { dimensions: 256 }
+    }
+}
+
+impl Embedder for HashingEmbedder {
+    fn embed(&self, text: &str) -> Vec<f32> {
+        let mut vector = vec![0.0f32; self.dimensions];
+        for term in EnglishTokenizer.tokenize(text) {
+            vector[(stable_hash(term.as_bytes()) % self.dimensions as u64) as usize] += 1.0;
+        }
+        let norm = vector.iter().map(|x| x * x).sum::<f32>().sqrt();
+        if norm > 0.0 {
+            vector.iter_mut().for_each(|x| *x /= norm);
+        }
+        vector
+    }
+
+    fn id(&self) -> String {
+        format!("hashing-fnv1a-{}", self.dimensions)
+    }
+}
+
+/// Cosine similarity of two vectors of the same length; 0.0 if either is all zeros.
+pub fn cosine(a: &[f32], b: &[f32]) -> f32 {
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm = a.iter().map(|x| x * x).sum::<f32>().sqrt() * b.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm > 0.0 { dot / norm } else { 0.0 }
+}
+
+/// The text an endpoint is embedded from: its name, description and parameters.
+fn embedding_text(endpoint: &ApiEndpoint) -> String {
+    let mut text = format!("{}\n{}", endpoint.name, endpoint.description);
+    for param in &endpoint.parameters {
+        text.push_str(&format!("\n{} {}", param.name, param.description));
+    }
+    text
+}
+
+/// Fingerprint of everything [`embedding_text`] reads, so an unchanged endpoint can keep its
+/// cached vector across re-scrapes.
+fn content_hash(endpoint: &ApiEndpoint) -> String {
+    let params: Vec<_> = endpoint.parameters
+        .iter()
+        .map(|param| (&param.name, param.param_type.to_string(), &param.description, param.required))
+        .collect();
+    let content = serde_json::to_string(&(&endpoint.name, &endpoint.description, params)).unwrap_or_default();
+    format!("{:016x}", stable_hash(content.as_bytes()))
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CachedEmbedding {
+    content_hash: String,
+    vector: Vec<f32>,
+}
+
+/// Endpoint embeddings keyed by `METHOD /path`, each stored with the content hash it was
+/// computed from. [`EmbeddingCache::refresh`] only re-embeds endpoints whose hash changed.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EmbeddingCache {
+    /// [`Embedder::id`] of the embedder that computed the vectors; empty in caches written
+    /// before it was recorded.
+    #[serde(default)]
+    embedder: String,
+    entries: BTreeMap<String, CachedEmbedding>,
+}
+
+impl EmbeddingCache {
+    /// Loads the cache at `path`; a missing file is an empty cache.
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read embedding cache {}", path.display()))?;
+        serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse embedding cache {}", path.display()))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_json::to_string(self)?)
+            .with_context(|| format!("Failed to write embedding cache {}", path.display()))
+    }
+
+    /// Brings the cache in line with `endpoints`: new or changed endpoints are embedded, unchanged
+    /// ones keep their vector, and entries for endpoints no longer present are dropped. Returns
+    /// how many endpoints were (re-)embedded. A cache built by a different embedder is discarded.
+    pub fn refresh(&mut self, endpoints: &[ApiEndpoint], embedder: &dyn Embedder) -> usize {
+        let embedder_id = embedder.id();
+        if self.embedder != embedder_id {
+            self.entries.clear();
+            self.embedder = embedder_id;
+        }
+        let mut entries = BTreeMap::new();
+        let mut embedded = 0;
+        for endpoint in endpoints {
+            let key = cache_key(endpoint);
+            let content_hash = content_hash(endpoint);
+            let entry = match self.entries.remove(&key) {
+                Some(cached) if cached.content_hash == content_hash => cached,
+                _ => {
+                    embedded += 1;
+                    CachedEmbedding { content_hash, vector: embedder.embed(&embedding_text(endpoint)) }
+                }
+            };
+            entries.insert(key, entry);
+        }
+        self.entries = entries;
+        embedded
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn vector(&self, endpoint: &ApiEndpoint) -> Option<&[f32]> {
+        self.entries.get(&cache_key(endpoint)).map(|entry| entry.vector.as_slice())
+    }
+}
+
+fn cache_key(endpoint: &ApiEndpoint) -> String {
+    format!("{} {}", endpoint.method, endpoint.path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::HttpMethod;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Counts calls so tests can tell cached vectors from fresh ones.
+    #[derive(Default)]
+    struct CountingEmbedder {
+        calls: AtomicUsize,
+    }
+
+    impl Embedder for CountingEmbedder {
+        fn embed(&self, text: &str) -> Vec<f32> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            HashingEmbedder::default().embed(text)
+        }
+
+        fn id(&self) -> String {
+            HashingEmbedder::default().id()
+        }
+    }
+
+    #[test]
+    fn test_refresh_reembeds_only_changed_endpoints() {
+        let embedder = CountingEmbedder::default();
+        let mut endpoints = vec![
+            ApiEndpoint::sample("Create Ticket", HttpMethod::Post, "/api/v2/tickets", "Create a new ticket"),
+            ApiEndpoint::sample("Delete Ticket", HttpMethod::Delete, "/api/v2/tickets/{id}", "Delete a ticket"),
+        ];
+        let mut cache = EmbeddingCache::default();
+        assert_eq!(cache.refresh(&endpoints, &embedder), 2);
+        let unchanged = cache.vector(&endpoints[0]).unwrap().to_vec();
+
+        endpoints[1].description = "Move a ticket to the trash".to_string();
+        endpoints[0].curl_example = Some("curl -X POST 'https://domain.freshservice.com/api/v2/tickets'".to_string());
+        assert_eq!(cache.refresh(&endpoints, &embedder), 1);
+        assert_eq!(embedder.calls.load(Ordering::SeqCst), 3);
+        assert_eq!(cache.vector(&endpoints[0]).unwrap(), unchanged.as_slice());
+        assert_eq!(cache.vector(&endpoints[1]).unwrap(), HashingEmbedder::default().embed(&embedding_text(&endpoints[1])).as_slice());
+
+        endpoints.pop();
+        assert_eq!(cache.refresh(&endpoints, &embedder), 0);
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[test]
+    fn test_cache_from_another_embedder_is_discarded() {
+        let endpoints = vec![ApiEndpoint::sample("Create Ticket", HttpMethod::Post, "/api/v2/tickets", "Create a new ticket")];
+        let mut cache = EmbeddingCache::default();
+        assert_eq!(cache.refresh(&endpoints, &HashingEmbedder::default()), 1);
+        assert_eq!(cache.refresh(&endpoints, &HashingEmbedder::default()), 0);
+
+        let wider = HashingEmbedder { dimensions: 512 };
+        assert_eq!(cache.refresh(&endpoints, &wider), 1);
+        assert_eq!(cache.vector(&endpoints[0]).unwrap().len(), 512);
+
+        // Caches written before the embedder was recorded are rebuilt too
+        let legacy: EmbeddingCache = serde_json::from_value(serde_json::json!({
+            "entries": {"POST /api/v2/tickets": {"content_hash": content_hash(&endpoints[0]), "vector": [1.0]}}
+        })).unwrap();
+        assert_eq!(legacy.clone().refresh(&endpoints, &HashingEmbedder::default()), 1);
+    }
+
+    #[test]
+    fn test_hashing_embedder_similarity() {
+        let embedder = HashingEmbedder::default();
+        let ticket = embedder.embed("Create a new ticket for the requester");
+
+        assert!(cosine(&ticket, &embedder.embed("create tickets")) > cosine(&ticket, &embedder.embed("list asset types")));
+        assert_eq!(cosine(&ticket, &embedder.embed("how do I")), 0.0);
+    }
+}
@@ -0,0 +1,110 @@
+use crate::config::FeedbackConfig;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use tokio::io::AsyncWriteExt;
+
+/// One line of the feedback log: a rating of the answer to `query`, and the endpoints that
+/// answer was built from.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeedbackEntry {
+    pub query: String,
+    pub helpful: bool,
+    pub endpoints: Vec<String>,
+    pub recorded_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Appends `entry` to the JSONL feedback log at `path`, creating the file if needed.
+pub async fn append_feedback(path: &Path, entry: &FeedbackEntry) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+    let mut file = tokio::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .await
+        .with_context(|| format!("Failed to open feedback log {}", path.display()))?;
+    // One write per entry so concurrent appends don't interleave within a line
+    file.write_all(format!("{}\n", serde_json::to_string(entry)?).as_bytes()).await?;
+    // tokio finishes the write in the background; flush so it lands before we report success
+    file.flush().await?;
+    Ok(())
+}
+
+/// Per-endpoint score boosts learned from the feedback log. Each helpful rating adds
+/// `boost_per_vote` and each unhelpful one takes it away; the result is clamped to
+/// `[0, max_boost]` so a popular endpoint can't crowd out better matches.
+#[derive(Debug, Clone, Default)]
+pub struct FeedbackBoosts {
+    boosts: HashMap<String, f32>,
+}
+
+impl FeedbackBoosts {
+    pub fn from_entries(entries: &[FeedbackEntry], config: &FeedbackConfig) -> Self {
+        let mut votes: HashMap<&str, i32> = HashMap::new();
+        for entry in entries {
+            for endpoint in &entry.endpoints {
+                *votes.entry(endpoint).or_default() += if entry.helpful { 1 } else { -1 };
+            }
+        }
+
+        let boosts = votes.into_iter()
+            .map(|(endpoint, net)| (endpoint.to_string(), (net as f32 * config.boost_per_vote).clamp(0.0, config.max_boost)))
+            .filter(|(_, boost)| *boost > 0.0)
+            .collect();
+        Self { boosts }
+    }
+
+    /// Loads boosts from the log at `config.log`. A missing log means no boosts; malformed
+    /// lines are skipped.
+    pub fn load(config: &FeedbackConfig) -> Result<Self> {
+        if !config.log.exists() {
+            return Ok(Self::default());
+        }
+        let content = std::fs::read_to_string(&config.log)
+            .with_context(|| format!("Failed to read feedback log {}", config.log.display()))?;
+        let entries: Vec<FeedbackEntry> = content.lines()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .collect();
+        Ok(Self::from_entries(&entries, config))
+    }
+
+    pub fn get(&self, endpoint_name: &str) -> f32 {
+        self.boosts.get(endpoint_name).copied().unwrap_or(0.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(helpful: bool, endpoints: &[&str]) -> FeedbackEntry {
+        FeedbackEntry {
+            query: "create a ticket".to_string(),
+            helpful,
+            endpoints: endpoints.iter().map(|e| e.to_string()).collect(),
+            recorded_at: chrono::Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_boosts_are_net_of_negative_votes_and_capped() {
+        let config = FeedbackConfig { boost_per_vote: 0.02, max_boost: 0.05, ..Default::default() };
+        let entries = vec![
+            entry(true, &["Create Ticket", "Update Ticket"]),
+            entry(true, &["Create Ticket"]),
+            entry(true, &["Create Ticket"]),
+            entry(true, &["Create Ticket"]),
+            entry(false, &["Update Ticket"]),
+            entry(false, &["Delete Ticket"]),
+        ];
+
+        let boosts = FeedbackBoosts::from_entries(&entries, &config);
+
+        assert_eq!(boosts.get("Create Ticket"), 0.05);
+        assert_eq!(boosts.get("Update Ticket"), 0.0);
+        assert_eq!(boosts.get("Delete Ticket"), 0.0);
+    }
+}
@@ -0,0 +1,40 @@
+use super::index::raw_words;
+use std::collections::HashSet;
+
+/// Word n-gram length used to compare an answer with its context. Bigrams catch copied phrases
+/// ("ticket id", "api v2") without punishing reworded sentences as hard as longer n-grams would.
+const GROUNDING_NGRAM: usize = 2;
+
+/// Fraction of the answer's word bigrams that also occur in `context`, from 0.0 (nothing in the
+/// answer comes from the context) to 1.0 (every phrase does). Answers shorter than a bigram are
+/// compared word by word; an empty answer scores 0.0.
+pub fn grounding_score(answer: &str, context: &str) -> f32 {
+    let answer_words: Vec<String> = raw_words(answer).collect();
+    let n = GROUNDING_NGRAM.min(answer_words.len());
+    if n == 0 {
+        return 0.0;
+    }
+
+    let context_words: Vec<String> = raw_words(context).collect();
+    let context_ngrams: HashSet<&[String]> = context_words.windows(n).collect();
+    let answer_ngrams: Vec<&[String]> = answer_words.windows(n).collect();
+    let grounded = answer_ngrams.iter().filter(|ngram| context_ngrams.contains(*ngram)).count();
+    grounded as f32 / answer_ngrams.len() as f32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_grounding_score() {
+        let context = "Create Ticket (POST)\nPath: /api/v2/tickets\nParameters:\n  - email (string) [Required]: Email of the requester";
+
+        assert_eq!(grounding_score("Use POST /api/v2/tickets with the requester's email.", context), 3.0 / 9.0);
+        assert_eq!(grounding_score("Create Ticket via POST /api/v2/tickets", context), 3.0 / 6.0);
+        assert_eq!(grounding_score("Path: /api/v2/tickets", context), 1.0);
+        assert_eq!(grounding_score("Bananas are yellow", context), 0.0);
+        assert_eq!(grounding_score("email", context), 1.0);
+        assert_eq!(grounding_score("", context), 0.0);
+    }
+}
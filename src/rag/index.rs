@@ -0,0 +1,100 @@
+use super::Tokenizer;
+use crate::models::{ApiEndpoint, HttpMethod};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+/// Inverted lists over the endpoint catalog, used to narrow a query down to the endpoints that
+/// can score above zero before running the detailed scorer.
+///
+/// Every signal the scorer uses has a list here: words of the name, aliases, description, path
+/// and parameters (both raw and as the tokenizer produces them), plus the method, status codes,
+/// and whether a curl example exists. Text signals match by substring, so a query term selects
+/// every indexed word that contains it, which keeps the candidates a superset of a full scan.
+#[derive(Debug, Clone, Default)]
+pub struct InvertedIndex {
+    /// Every distinct word, newline-separated, so a substring lookup is one pass per term.
+    vocabulary: String,
+    /// Offset in `vocabulary` where each word starts, parallel to `postings`.
+    word_starts: Vec<usize>,
+    postings: Vec<Vec<usize>>,
+    len: usize,
+    methods: HashMap<HttpMethod, Vec<usize>>,
+    status_codes: HashMap<u16, Vec<usize>>,
+    with_curl: Vec<usize>,
+}
+
+impl InvertedIndex {
+    pub fn build(endpoints: &[ApiEndpoint], tokenizer: &dyn Tokenizer) -> Self {
+        let mut index = Self { len: endpoints.len(), ..Self::default() };
+        let mut vocabulary: BTreeMap<String, Vec<usize>> = BTreeMap::new();
+
+        for (i, endpoint) in endpoints.iter().enumerate() {
+            let mut words = BTreeSet::new();
+            let parameter_text = endpoint.parameters.iter().flat_map(|param| [&param.name, &param.description]);
+            let texts = std::iter::once(&endpoint.name)
+                .chain(&endpoint.aliases)
+                .chain([&endpoint.description, &endpoint.path])
+                .chain(parameter_text);
+            for text in texts {
+                words.extend(raw_words(text));
+                words.extend(tokenizer.tokenize(text));
+            }
+            for word in words {
+                vocabulary.entry(word).or_default().push(i);
+            }
+
+            index.methods.entry(endpoint.method).or_default().push(i);
+            for code in &endpoint.status_codes {
+                index.status_codes.entry(*code).or_default().push(i);
+            }
+            if endpoint.curl_example.is_some() {
+                index.with_curl.push(i);
+            }
+        }
+
+        for (word, postings) in vocabulary {
+            index.word_starts.push(index.vocabulary.len());
+            index.vocabulary.push_str(&word);
+            index.vocabulary.push('\n');
+            index.postings.push(postings);
+        }
+        index
+    }
+
+    /// Flags, by catalog position, the endpoints with at least one indexed word containing one
+    /// of `terms`.
+    pub fn containing_any(&self, terms: &[String]) -> Vec<bool> {
+        let mut marked = vec![false; self.len];
+        for term in terms {
+            let mut last_word = None;
+            for (offset, _) in self.vocabulary.match_indices(term.as_str()) {
+                let word = self.word_starts.partition_point(|&start| start <= offset) - 1;
+                if last_word.replace(word) == Some(word) {
+                    continue;
+                }
+                for &i in &self.postings[word] {
+                    marked[i] = true;
+                }
+            }
+        }
+        marked
+    }
+
+    pub fn with_method(&self, method: HttpMethod) -> &[usize] {
+        self.methods.get(&method).map(Vec::as_slice).unwrap_or_default()
+    }
+
+    pub fn with_status(&self, code: u16) -> &[usize] {
+        self.status_codes.get(&code).map(Vec::as_slice).unwrap_or_default()
+    }
+
+    pub fn with_curl(&self) -> &[usize] {
+        &self.with_curl
+    }
+}
+
+/// Lowercased runs of letters, digits and `_`, without stopword removal or stemming.
+pub fn raw_words(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !c.is_alphanumeric() && c != '_')
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+}
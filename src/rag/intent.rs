@@ -0,0 +1,89 @@
+use crate::models::HttpMethod;
+
+/// What the user wants to do, inferred from the verbs in the query.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Intent {
+    Create,
+    Update,
+    Delete,
+    Read,
+}
+
+impl Intent {
+    /// Classifies by the first recognized verb in the query, e.g. "how do I remove a note" is Delete.
+    pub fn classify(query: &str) -> Option<Self> {
+        query.split(|c: char| !c.is_alphanumeric())
+            .find_map(|word| Self::from_verb(&word.to_lowercase()))
+    }
+
+    fn from_verb(word: &str) -> Option<Self> {
+        match word {
+            // Words that double as adjectives or nouns ("open tickets", "new tickets") are left out
+            "create" | "add" | "make" | "raise" | "submit" | "post" => Some(Intent::Create),
+            "update" | "modify" | "edit" | "change" | "put" | "patch" => Some(Intent::Update),
+            "delete" | "remove" | "destroy" | "erase" | "trash" => Some(Intent::Delete),
+            "list" | "get" | "show" | "view" | "fetch" | "retrieve" | "read" => Some(Intent::Read),
+            _ => None,
+        }
+    }
+
+    /// The HTTP methods endpoints serving this intent use; updates may be full (PUT) or partial (PATCH).
+    pub fn methods(&self) -> &'static [HttpMethod] {
+        match self {
+            Intent::Create => &[HttpMethod::Post],
+            Intent::Update => &[HttpMethod::Put, HttpMethod::Patch],
+            Intent::Delete => &[HttpMethod::Delete],
+            Intent::Read => &[HttpMethod::Get],
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Intent::Create => "create",
+            Intent::Update => "update",
+            Intent::Delete => "delete",
+            Intent::Read => "read",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_verb_classes_map_to_methods() {
+        let update: &[HttpMethod] = &[HttpMethod::Put, HttpMethod::Patch];
+        let cases: [(&str, &[HttpMethod]); 10] = [
+            ("How do I create a ticket?", &[HttpMethod::Post]),
+            ("add a note to a ticket", &[HttpMethod::Post]),
+            ("update ticket priority", update),
+            ("Modify the requester", update),
+            ("patch a ticket's status", update),
+            ("remove a ticket", &[HttpMethod::Delete]),
+            ("delete time entry", &[HttpMethod::Delete]),
+            ("list all tickets", &[HttpMethod::Get]),
+            ("get ticket by id", &[HttpMethod::Get]),
+            ("show me ticket tasks", &[HttpMethod::Get]),
+        ];
+
+        for (query, methods) in cases {
+            assert_eq!(Intent::classify(query).map(|intent| intent.methods()), Some(methods), "{}", query);
+        }
+    }
+
+    #[test]
+    fn test_first_verb_wins_and_unknown_is_none() {
+        assert_eq!(Intent::classify("list tickets then delete one"), Some(Intent::Read));
+        assert_eq!(Intent::classify("ticket attributes"), None);
+        // Verbs must be whole words
+        assert_eq!(Intent::classify("addresses of requesters"), None);
+    }
+
+    #[test]
+    fn test_adjectives_are_not_verbs() {
+        assert_eq!(Intent::classify("open tickets by requester"), None);
+        assert_eq!(Intent::classify("new tickets this week"), None);
+        assert_eq!(Intent::classify("list new tickets this week"), Some(Intent::Read));
+    }
+}
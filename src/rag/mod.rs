@@ -1,3 +1,20 @@
+mod blocklist;
+mod embedding;
+mod feedback;
+mod grounding;
+mod index;
+mod intent;
 mod pipeline;
+mod router;
+mod snippet;
+mod suggest;
+mod tokenizer;
 
-pub use pipeline::RagPipeline;
\ No newline at end of file
+pub use blocklist::Blocklist;
+pub use embedding::{EmbeddingCache, HashingEmbedder};
+pub use feedback::{append_feedback, FeedbackBoosts, FeedbackEntry};
+pub use grounding::grounding_score;
+pub use intent::Intent;
+pub use pipeline::{RagPipeline, StructuredAnswer};
+pub use router::RagRouter;
+pub use tokenizer::{tokenizer_for, EnglishTokenizer, Tokenizer};
@@ -1,23 +1,189 @@
-use crate::models::{ApiEndpoint, ScrapedDocumentation};
+use super::embedding::{cosine, Embedder, EmbeddingCache};
+use super::index::{raw_words, InvertedIndex};
+use super::snippet::extract_snippet;
+use super::{Blocklist, EnglishTokenizer, FeedbackBoosts, Intent, Tokenizer};
+use crate::config::{ContextConfig, ScoringWeights};
+use crate::models::{json_type_name, ApiEndpoint, ApiParameter, HttpMethod, ScrapedDocumentation};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+/// Length of the description excerpt attached to each search result.
+const SNIPPET_WINDOW: usize = 160;
+
+/// Endpoints on the same path scoring within this much of the path's best match count as tied
+/// and, for queries without a method intent, are ordered by method instead.
+const SAME_PATH_TIE: f32 = 0.05;
+
+/// Longest run of query terms matched as an exact phrase in endpoint names and descriptions.
+const MAX_PHRASE_WORDS: usize = 3;
+
+/// Rough characters-per-token ratio used to turn the context token budget into a length limit.
+const CHARS_PER_TOKEN: usize = 4;
+
+/// Machine-readable summary of the best-matching endpoint, for integrations that call the API
+/// rather than read prose.
+#[derive(Debug, Clone, Serialize)]
+pub struct StructuredAnswer {
+    pub name: String,
+    pub method: HttpMethod,
+    pub path: String,
+    pub required_params: Vec<String>,
+    /// The endpoint's curl example, if its documentation has one.
+    pub example: Option<String>,
+}
+
+/// Per-signal contributions to a relevance score, before normalization. `total` is the normalized score.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ScoreBreakdown {
+    pub name: f32,
+    pub description: f32,
+    pub path: f32,
+    pub method: f32,
+    pub parameters: f32,
+    pub curl: f32,
+    pub status_code: f32,
+    /// Learned boost from helpful-answer feedback, added after normalization.
+    pub feedback: f32,
+    /// Embedding similarity bonus, added after normalization when embeddings are enabled.
+    pub semantic: f32,
+    pub total: f32,
+}
+
+impl std::fmt::Display for ScoreBreakdown {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{:.2} (name {:.2}, description {:.2}, path {:.2}, method {:.2}, parameters {:.2}, curl {:.2}, status code {:.2}, feedback {:.2}, semantic {:.2})",
+            self.total, self.name, self.description, self.path, self.method, self.parameters, self.curl, self.status_code, self.feedback, self.semantic
+        )
+    }
+}
+
+/// A ranked endpoint returned by the LLM-free search mode.
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchResult {
+    pub name: String,
+    pub method: HttpMethod,
+    pub path: String,
+    pub score: f32,
+    pub snippet: String,
+}
 
 #[derive(Clone)]
 pub struct RagPipeline {
     documentation: ScrapedDocumentation,
+    documentation_hash: String,
+    weights: ScoringWeights,
+    context_config: ContextConfig,
+    feedback_boosts: FeedbackBoosts,
+    tokenizer: Arc<dyn Tokenizer>,
+    environments: BTreeMap<String, String>,
+    index: Arc<InvertedIndex>,
+    embeddings: Option<Arc<Embeddings>>,
+}
+
+/// Cached endpoint vectors, the embedder that produced them (used again for queries), and the
+/// most the similarity may add to a score.
+struct Embeddings {
+    cache: EmbeddingCache,
+    embedder: Box<dyn Embedder>,
+    weight: f32,
 }
 
 impl RagPipeline {
     pub fn new(documentation: ScrapedDocumentation) -> Self {
-        Self { documentation }
+        let tokenizer: Arc<dyn Tokenizer> = Arc::new(EnglishTokenizer);
+        Self {
+            documentation_hash: documentation.content_hash(),
+            index: Arc::new(InvertedIndex::build(&documentation.endpoints, tokenizer.as_ref())),
+            documentation,
+            weights: ScoringWeights::default(),
+            context_config: ContextConfig::default(),
+            feedback_boosts: FeedbackBoosts::default(),
+            tokenizer,
+            environments: BTreeMap::new(),
+            embeddings: None,
+        }
+    }
+    
+    fn rebuild_index(&mut self) {
+        self.index = Arc::new(InvertedIndex::build(&self.documentation.endpoints, self.tokenizer.as_ref()));
+    }
+    
+    /// Named base URLs (e.g. `sandbox`) that queries can refer to.
+    pub fn with_environments(mut self, environments: BTreeMap<String, String>) -> Self {
+        self.environments = environments;
+        self
+    }
+    
+    /// Replaces the default English tokenizer used to split queries into scoring terms.
+    pub fn with_tokenizer(mut self, tokenizer: Box<dyn Tokenizer>) -> Self {
+        self.tokenizer = Arc::from(tokenizer);
+        self.rebuild_index();
+        self
     }
     
+    /// Drops blocked endpoints from the catalog, so they can't reach matches, context, or any
+    /// listing built from the documentation.
+    pub fn with_blocklist(mut self, blocklist: &Blocklist) -> Self {
+        self.documentation.endpoints.retain(|endpoint| {
+            let blocked = blocklist.blocks(endpoint);
+            if blocked {
+                tracing::info!(method = %endpoint.method, path = %endpoint.path, name = %endpoint.name, "blocked endpoint filtered");
+            }
+            !blocked
+        });
+        self.documentation_hash = self.documentation.content_hash();
+        self.rebuild_index();
+        self
+    }
+    
+    /// Adds up to `weight` to matching endpoints in proportion to the cosine similarity between
+    /// the query's embedding and the endpoint's vector in `cache`.
+    pub fn with_embeddings(mut self, cache: EmbeddingCache, embedder: Box<dyn Embedder>, weight: f32) -> Self {
+        self.embeddings = Some(Arc::new(Embeddings { cache, embedder, weight }));
+        self
+    }
+    
+    pub fn with_feedback_boosts(mut self, feedback_boosts: FeedbackBoosts) -> Self {
+        self.feedback_boosts = feedback_boosts;
+        self
+    }
+    
+    pub fn with_context_config(mut self, context_config: ContextConfig) -> Self {
+        self.context_config = context_config;
+        self
+    }
+    
+    pub fn with_weights(mut self, weights: ScoringWeights) -> Self {
+        self.weights = weights;
+        self
+    }
+    
+    /// Matches a query is answered from, best first: at most `max_returned` of them.
     pub fn find_relevant_endpoints(&self, query: &str) -> Vec<(&ApiEndpoint, f32)> {
+        let mut matches = self.ranked_endpoints(query);
+        matches.truncate(self.weights.max_returned);
+        matches
+    }
+    
+    /// Every endpoint scoring above `score_floor`, best first. Searches take their own limit
+    /// from here rather than being capped at `max_returned`.
+    fn ranked_endpoints(&self, query: &str) -> Vec<(&ApiEndpoint, f32)> {
         let query_lower = query.to_lowercase();
+        let endpoints = &self.documentation.endpoints;
         
-        let mut matches: Vec<_> = self.documentation.endpoints
-            .iter()
+        let candidates: Box<dyn Iterator<Item = &ApiEndpoint>> = match self.candidates(&query_lower) {
+            Some(indices) => Box::new(indices.into_iter().map(|i| &endpoints[i])),
+            None => Box::new(endpoints.iter()),
+        };
+        // Embedded once here rather than per scored endpoint
+        let query_vector = self.query_embedding(&query_lower);
+        let mut matches: Vec<_> = candidates
             .filter_map(|endpoint| {
-                let score = self.calculate_relevance_score(endpoint, &query_lower);
-                if score > 0.1 {
+                let score = self.calculate_relevance_score(endpoint, &query_lower, query_vector.as_deref());
+                if score > self.weights.score_floor {
                     Some((endpoint, score))
                 } else {
                     None
@@ -27,106 +193,489 @@ impl RagPipeline {
         
         // Sort by relevance score (descending)
         matches.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        if Intent::classify(&query_lower).is_none() {
+            Self::order_same_path_by_method(&mut matches);
+        }
         
         matches
     }
     
-    fn calculate_relevance_score(&self, endpoint: &ApiEndpoint, query_lower: &str) -> f32 {
-        let query_words: Vec<&str> = query_lower.split_whitespace().collect();
-        let mut score = 0.0f32;
-
-        // Check name (highest weight: 2.5 max)
-        let name_lower = endpoint.name.to_lowercase();
-        if name_lower.contains(query_lower) {
-            score += 2.0;
+    /// Without a method in the query, endpoints sharing a path (GET/POST/PUT/DELETE on
+    /// `/tickets`) score almost the same and their order is arbitrary. Near-ties on a path are
+    /// grouped at the position of the path's best match and listed in canonical method order.
+    /// `matches` must already be sorted by score.
+    fn order_same_path_by_method(matches: &mut Vec<(&ApiEndpoint, f32)>) {
+        let mut by_path: std::collections::HashMap<&str, Vec<usize>> = std::collections::HashMap::new();
+        for (i, (endpoint, _)) in matches.iter().enumerate() {
+            by_path.entry(endpoint.path.as_str()).or_default().push(i);
         }
-        score += query_words.iter()
-            .filter(|word| name_lower.contains(*word))
-            .count() as f32 * 0.5;
+        
+        let mut placed = vec![false; matches.len()];
+        let mut ordered = Vec::with_capacity(matches.len());
+        for i in 0..matches.len() {
+            if placed[i] {
+                continue;
+            }
+            let (endpoint, best) = matches[i];
+            let mut tied: Vec<usize> = by_path[endpoint.path.as_str()]
+                .iter()
+                .copied()
+                .filter(|&j| !placed[j])
+                .take_while(|&j| best - matches[j].1 <= SAME_PATH_TIE)
+                .collect();
+            tied.sort_by_key(|&j| (matches[j].0.method, j));
+            for j in tied {
+                placed[j] = true;
+                ordered.push(matches[j]);
+            }
+        }
+        *matches = ordered;
+    }
+    
+    /// Catalog positions of the endpoints that can score above zero for `query_lower`, in catalog
+    /// order, or `None` when the query has no terms and every endpoint must be scored.
+    fn candidates(&self, query_lower: &str) -> Option<Vec<usize>> {
+        // Word signals match tokenizer terms; whole-query signals ("name contains the query") can
+        // only fire where the query's longest word appears too
+        let mut terms = self.tokenizer.tokenize(query_lower);
+        terms.extend(raw_words(query_lower).max_by_key(String::len));
+        if terms.is_empty() {
+            return None;
+        }
+        
+        let mut marked = self.index.containing_any(&terms);
+        let mut mark = |postings: &[usize]| postings.iter().for_each(|&i| marked[i] = true);
+        if let Some(intent) = Intent::classify(query_lower) {
+            for &method in intent.methods() {
+                mark(self.index.with_method(method));
+            }
+        }
+        if query_lower.contains("curl") {
+            mark(self.index.with_curl());
+        }
+        for code in Self::status_codes_in(query_lower) {
+            mark(self.index.with_status(code));
+        }
+        Some(marked.iter().enumerate().filter(|(_, marked)| **marked).map(|(i, _)| i).collect())
+    }
+    
+    fn calculate_relevance_score(&self, endpoint: &ApiEndpoint, query_lower: &str, query_vector: Option<&[f32]>) -> f32 {
+        self.score_breakdown_with(endpoint, query_lower, query_vector).total
+    }
+    
+    /// Weighted contribution of each signal to an endpoint's relevance for `query_lower`.
+    pub fn score_breakdown(&self, endpoint: &ApiEndpoint, query_lower: &str) -> ScoreBreakdown {
+        self.score_breakdown_with(endpoint, query_lower, self.query_embedding(query_lower).as_deref())
+    }
+    
+    /// The query's embedding, when embeddings are enabled.
+    fn query_embedding(&self, query_lower: &str) -> Option<Vec<f32>> {
+        self.embeddings.as_ref().map(|embeddings| embeddings.embedder.embed(query_lower))
+    }
+    
+    /// [`Self::score_breakdown`] with the query's embedding computed by the caller.
+    fn score_breakdown_with(&self, endpoint: &ApiEndpoint, query_lower: &str, query_vector: Option<&[f32]>) -> ScoreBreakdown {
+        let query_words = self.tokenizer.tokenize(query_lower);
+        let weights = &self.weights;
+        let mut breakdown = ScoreBreakdown::default();
+
+        // Check name and aliases (highest weight: 2.5 max). A key phrase of the query ("time
+        // entries") earns 0.5 per word, between a single-word hit and the whole query.
+        let name_score = std::iter::once(&endpoint.name)
+            .chain(&endpoint.aliases)
+            .map(|name| {
+                let name_lower = name.to_lowercase();
+                let name_words = self.tokenizer.tokenize(&name_lower);
+                let mut name_score = 0.0f32;
+                if name_lower.contains(query_lower) {
+                    name_score += 2.0;
+                } else {
+                    name_score += Self::longest_phrase_in(&query_words, &name_words) as f32 * 0.5;
+                }
+                name_score + query_words.iter()
+                    .filter(|word| Self::mentions(&name_lower, &name_words, word))
+                    .count() as f32 * 0.5
+            })
+            .fold(0.0f32, f32::max);
+        breakdown.name = weights.name * name_score / 2.5;
 
         // Check description (1.3 max)
         let desc_lower = endpoint.description.to_lowercase();
+        let desc_words = self.tokenizer.tokenize(&desc_lower);
+        let mut desc_score = 0.0f32;
         if desc_lower.contains(query_lower) {
-            score += 1.0;
+            desc_score += 1.0;
+        } else {
+            desc_score += Self::longest_phrase_in(&query_words, &desc_words) as f32 * 0.3;
         }
-        score += query_words.iter()
-            .filter(|word| desc_lower.contains(*word))
+        desc_score += query_words.iter()
+            .filter(|word| Self::mentions(&desc_lower, &desc_words, word))
             .count() as f32 * 0.3;
+        breakdown.description = weights.description * desc_score / 1.3;
 
         // Check path (0.8 max)
         if endpoint.path.to_lowercase().contains(query_lower) {
-            score += 0.8;
+            breakdown.path = weights.path;
         }
 
-        // Check HTTP method match (0.8 max)
-        let method_lower = endpoint.method.to_lowercase();
-        if query_words.iter().any(|word| {
-            method_lower == *word || 
-            (method_lower == "get" && *word == "list") ||
-            (method_lower == "get" && *word == "view") ||
-            (method_lower == "get" && *word == "fetch")
-        }) {
-            score += 0.8;
+        // Check HTTP method against the query's intent ("remove" -> DELETE) (0.8 max)
+        if Intent::classify(query_lower).is_some_and(|intent| intent.methods().contains(&endpoint.method)) {
+            breakdown.method = weights.method;
         }
 
         // Check parameters (0.6 max)
         for param in &endpoint.parameters {
             if param.name.to_lowercase().contains(query_lower) {
-                score += 0.4;
+                breakdown.parameters = weights.parameters * 0.4 / 0.6;
                 break;
             }
             if param.description.to_lowercase().contains(query_lower) {
-                score += 0.2;
+                breakdown.parameters = weights.parameters * 0.2 / 0.6;
                 break;
             }
         }
 
         // Check for curl example (1.0 if query mentions curl)
         if query_lower.contains("curl") && endpoint.curl_example.is_some() {
-            score += 1.0;
+            breakdown.curl = weights.curl;
         }
 
-        // Normalize score to 0-1 range (max theoretical: ~7)
-        (score / 7.0).min(1.0)
+        // Check status codes mentioned in the query, e.g. "which endpoint returns 204" (0.5 max)
+        if Self::status_codes_in(query_lower).any(|code| endpoint.status_codes.contains(&code)) {
+            breakdown.status_code = weights.status_code;
+        }
+
+        // Normalize score to 0-1 range (max theoretical: sum of core weights; bonuses ride on top)
+        let raw = breakdown.name + breakdown.description + breakdown.path
+            + breakdown.method + breakdown.parameters + breakdown.curl + breakdown.status_code;
+        // Feedback only lifts endpoints that already match; it never makes an unrelated one relevant
+        if raw > 0.0 {
+            breakdown.feedback = self.feedback_boosts.get(&endpoint.name);
+            if let Some(embeddings) = &self.embeddings
+                && let Some(query_vector) = query_vector
+                && let Some(vector) = embeddings.cache.vector(endpoint)
+            {
+                let similarity = cosine(query_vector, vector);
+                breakdown.semantic = embeddings.weight * similarity.max(0.0);
+            }
+        }
+        breakdown.total = (raw / weights.total() + breakdown.feedback + breakdown.semantic).min(1.0);
+        breakdown
+    }
+    
+    /// Whether the query term `word` occurs in a text: as a substring of the raw text, or as one
+    /// of its tokenizer terms, since query terms are stemmed ("entries" -> "entry") and the raw
+    /// text is not.
+    fn mentions(text_lower: &str, text_words: &[String], word: &str) -> bool {
+        text_lower.contains(word) || text_words.iter().any(|text_word| text_word == word)
+    }
+    
+    /// Length of the longest 2-3 term run of `query_words` that appears, in order, among
+    /// `text_words`, or 0 when none does. Comparing terms rather than raw text lets "time
+    /// entries" match "Time Entry".
+    fn longest_phrase_in(query_words: &[String], text_words: &[String]) -> usize {
+        if query_words.len() < 2 {
+            return 0;
+        }
+        (2..=MAX_PHRASE_WORDS.min(query_words.len()))
+            .rev()
+            .find(|&n| query_words.windows(n).any(|phrase| text_words.windows(n).any(|run| run == phrase)))
+            .unwrap_or(0)
+    }
+    
+    fn status_codes_in(query_lower: &str) -> impl Iterator<Item = u16> + '_ {
+        query_lower
+            .split(|c: char| !c.is_ascii_digit())
+            .filter(|token| token.len() == 3)
+            .filter_map(|token| token.parse::<u16>().ok())
+            .filter(|code| (100..600).contains(code))
     }
     
+    /// Up to `limit` distinct example questions derived from the catalog, each paired with the
+    /// endpoint it is about.
+    pub fn suggested_questions(&self, limit: usize) -> Vec<(String, &ApiEndpoint)> {
+        let mut seen = std::collections::HashSet::new();
+        self.documentation.endpoints
+            .iter()
+            .filter_map(|endpoint| super::suggest::question_for(endpoint).map(|question| (question, endpoint)))
+            .filter(|(question, _)| seen.insert(question.clone()))
+            .take(limit)
+            .collect()
+    }
+    
+    pub fn endpoints_with_status(&self, code: u16) -> Vec<&ApiEndpoint> {
+        self.documentation.endpoints
+            .iter()
+            .filter(|endpoint| endpoint.status_codes.contains(&code))
+            .collect()
+    }
+    
+    /// Formats matches into LLM context, taking them in score order until the token budget is
+    /// spent or the next match falls below `min_score_ratio` of the top score.
     pub fn format_context(&self, matches: &[(&ApiEndpoint, f32)]) -> (String, f32) {
         if matches.is_empty() {
             return (String::from("No relevant endpoints found."), 0.0);
         }
 
-        let mut context = String::with_capacity(matches.len() * 200);
-        let max_score = matches.first().map(|(_, s)| *s).unwrap_or(0.0);
+        let max_score = matches.iter().map(|(_, s)| *s).fold(0.0f32, f32::max);
+        let included = self.context_matches(matches);
         
-        for (endpoint, score) in matches.iter().take(5) {  // Limit to top 5
-            context.push_str(&format!(
-                "[Relevance: {:.2}] {} ({})\n\
-                 Description: {}\n\
-                 Path: {}\n",
-                score, endpoint.name, endpoint.method,
-                endpoint.description, endpoint.path
-            ));
-            
-            if !endpoint.parameters.is_empty() {
-                context.push_str("Parameters:\n");
-                for param in &endpoint.parameters {
-                    context.push_str(&format!(
-                        "  - {} ({}){}: {}\n",
-                        param.name, param.param_type,
-                        if param.required { " [Required]" } else { "" },
-                        param.description
-                    ));
-                }
+        let (shared_notes, shared) = self.shared_descriptions(&included);
+        let mut context = shared_notes;
+        for (i, (endpoint, score)) in included.iter().enumerate() {
+            context.push_str(&Self::format_endpoint(endpoint, *score, shared[i]));
+        }
+        
+        (context, max_score)
+    }
+    
+    /// The leading `matches` that [`Self::format_context`] puts in the context, within
+    /// `max_endpoints`, the score cliff and the token budget.
+    pub fn context_matches<'a>(&self, matches: &[(&'a ApiEndpoint, f32)]) -> Vec<(&'a ApiEndpoint, f32)> {
+        let limits = &self.context_config;
+        let budget_chars = limits.token_budget * CHARS_PER_TOKEN;
+        let max_score = matches.iter().map(|(_, s)| *s).fold(0.0f32, f32::max);
+        let mut included = Vec::new();
+        let mut used_chars = 0;
+        
+        for (i, (endpoint, score)) in matches.iter().take(limits.max_endpoints).enumerate() {
+            // The best match is always included; later ones must clear the score cliff and fit the budget
+            if i > 0 && *score < max_score * limits.min_score_ratio {
+                break;
             }
             
-            if let Some(curl) = &endpoint.curl_example {
-                context.push_str(&format!("cURL Example:\n{}\n", curl));
+            let block_len = Self::format_endpoint(endpoint, *score, false).len();
+            if i > 0 && used_chars + block_len > budget_chars {
+                break;
+            }
+            used_chars += block_len;
+            included.push((*endpoint, *score));
+        }
+        included
+    }
+    
+    /// Groups included endpoints whose descriptions overlap by at least `description_similarity`
+    /// and returns a note per group of two or more, plus which endpoints the notes cover.
+    fn shared_descriptions(&self, included: &[(&ApiEndpoint, f32)]) -> (String, Vec<bool>) {
+        let word_sets: Vec<std::collections::HashSet<String>> = included
+            .iter()
+            .map(|(endpoint, _)| raw_words(&endpoint.description).collect())
+            .collect();
+        let mut shared = vec![false; included.len()];
+        let mut notes = String::new();
+        
+        for leader in 0..included.len() {
+            if shared[leader] || word_sets[leader].is_empty() {
+                continue;
+            }
+            let members: Vec<usize> = (leader + 1..included.len())
+                .filter(|&other| !shared[other])
+                .filter(|&other| jaccard(&word_sets[leader], &word_sets[other]) >= self.context_config.description_similarity)
+                .collect();
+            if members.is_empty() {
+                continue;
             }
             
-            context.push_str("\n---\n\n");
+            let names: Vec<&str> = std::iter::once(leader)
+                .chain(members.iter().copied())
+                .map(|i| included[i].0.name.as_str())
+                .collect();
+            notes.push_str(&format!(
+                "Shared description ({}): {}\n\n",
+                names.join(", "), included[leader].0.description
+            ));
+            shared[leader] = true;
+            for member in members {
+                shared[member] = true;
+            }
         }
         
-        (context, max_score)
+        (notes, shared)
+    }
+    
+    /// LLM-free answer built from the single best match: its description, parameters and curl
+    /// example, exactly as they appear in the docs.
+    pub fn extractive_answer(&self, matches: &[(&ApiEndpoint, f32)]) -> Option<String> {
+        let (endpoint, score) = matches.first()?;
+        let (details, _) = self.format_context(&[(*endpoint, *score)]);
+        Some(format!(
+            "Use {} {} ({}).\n\n{}",
+            endpoint.method, endpoint.path, endpoint.name,
+            details.trim_end().trim_end_matches("---").trim_end()
+        ))
+    }
+    
+    /// The best match's curl example verbatim, or a note that it documents none. `None` when
+    /// nothing matched.
+    pub fn curl_answer(&self, matches: &[(&ApiEndpoint, f32)]) -> Option<String> {
+        let (endpoint, _) = matches.first()?;
+        Some(match &endpoint.curl_example {
+            Some(curl) => curl.clone(),
+            None => format!(
+                "{} ({} {}) best matches your question, but its documentation has no curl example.",
+                endpoint.name, endpoint.method, endpoint.path
+            ),
+        })
+    }
+    
+    /// The best match as a [`StructuredAnswer`]. `None` when nothing matched.
+    pub fn structured_answer(&self, matches: &[(&ApiEndpoint, f32)]) -> Option<StructuredAnswer> {
+        let (endpoint, _) = matches.first()?;
+        Some(StructuredAnswer {
+            name: endpoint.name.clone(),
+            method: endpoint.method,
+            path: endpoint.path.clone(),
+            required_params: endpoint.parameters
+                .iter()
+                .filter(|param| param.required)
+                .map(|param| param.name.clone())
+                .collect(),
+            example: endpoint.curl_example.clone(),
+        })
+    }
+    
+    /// One endpoint's context block. With `shared_description`, the description was already
+    /// printed in a shared note and is only referred to.
+    fn format_endpoint(endpoint: &ApiEndpoint, score: f32, shared_description: bool) -> String {
+        let description = if shared_description { "see shared description above" } else { &endpoint.description };
+        let mut block = format!(
+            "[Relevance: {:.2}] {} ({})\n\
+             Description: {}\n\
+             Path: {}\n",
+            score, endpoint.name, endpoint.method,
+            description, endpoint.path
+        );
+        
+        if !endpoint.parameters.is_empty() {
+            block.push_str("Parameters:\n");
+            Self::push_parameters(&mut block, &endpoint.parameters, 1);
+        }
+        
+        if let Some(pagination) = &endpoint.pagination {
+            block.push_str(&format!("Pagination: {}\n", pagination));
+        }
+        
+        if !endpoint.notes.is_empty() {
+            block.push_str("Notes:\n");
+            for note in &endpoint.notes {
+                block.push_str(&format!("  - {}\n", note));
+            }
+        }
+        
+        if let Some(curl) = &endpoint.curl_example {
+            block.push_str(&format!("cURL Example:\n{}\n", curl));
+        }
+        
+        block.push_str("\n---\n\n");
+        block
+    }
+    
+    fn push_parameters(context: &mut String, params: &[ApiParameter], depth: usize) {
+        for param in params {
+            context.push_str(&format!(
+                "{}- {} ({}){}: {}\n",
+                "  ".repeat(depth),
+                param.name, param.param_type,
+                if param.required { " [Required]" } else { "" },
+                param.description
+            ));
+            Self::push_parameters(context, &param.children, depth + 1);
+        }
+    }
+    
+    /// Top-level field names and JSON types of an endpoint's sample response. A single-key
+    /// envelope such as `{"ticket": {...}}` is unwrapped so the resource's own fields are listed.
+    pub fn response_fields(endpoint: &ApiEndpoint) -> Vec<(String, String)> {
+        let Some(mut schema) = endpoint.response_schema.as_ref() else {
+            return Vec::new();
+        };
+        if let Some(object) = schema.as_object()
+            && object.len() == 1
+            && let Some(inner) = object.values().next()
+            && (inner.is_object() || inner.get(0).is_some_and(|first| first.is_object()))
+        {
+            schema = inner;
+        }
+        // A list response describes its items
+        if let Some(first) = schema.as_array().and_then(|items| items.first()) {
+            schema = first;
+        }
+        
+        schema.as_object()
+            .map(|object| object.iter()
+                .map(|(name, value)| (name.clone(), json_type_name(value).to_string()))
+                .collect())
+            .unwrap_or_default()
+    }
+    
+    /// Response field lists of the top matches, for queries asking what a response contains.
+    pub fn response_context(&self, query: &str, matches: &[(&ApiEndpoint, f32)]) -> Option<String> {
+        let query_lower = query.to_lowercase();
+        if !["field", "response", "return"].iter().any(|term| query_lower.contains(term)) {
+            return None;
+        }
+        
+        let mut context = String::new();
+        for (endpoint, _) in matches.iter().take(self.context_config.max_endpoints) {
+            let fields = Self::response_fields(endpoint);
+            if fields.is_empty() {
+                continue;
+            }
+            context.push_str(&format!("Response fields of {} ({} {}):\n", endpoint.name, endpoint.method, endpoint.path));
+            for (name, field_type) in fields {
+                context.push_str(&format!("  - {} ({})\n", name, field_type));
+            }
+        }
+        (!context.is_empty()).then_some(context)
+    }
+    
+    /// A note naming the base URL to use when the query mentions a configured environment, either
+    /// by name ("in sandbox") or by its host or subdomain ("acme-sandbox").
+    pub fn environment_context(&self, query: &str) -> Option<String> {
+        let query_lower = query.to_lowercase();
+        let words: Vec<&str> = query_lower
+            .split(|c: char| !(c.is_alphanumeric() || c == '-' || c == '.' || c == '_'))
+            .map(|word| word.trim_matches('.'))
+            .filter(|word| !word.is_empty())
+            .collect();
+        
+        self.environments.iter().find_map(|(name, base_url)| {
+            let host = base_url.split("://").nth(1).unwrap_or(base_url).trim_end_matches('/').to_lowercase();
+            let subdomain = host.split('.').next().unwrap_or(&host);
+            let mentioned = words.iter().any(|word| word.eq_ignore_ascii_case(name) || *word == subdomain)
+                || query_lower.contains(&host);
+            mentioned.then(|| format!(
+                "Environment: the question refers to the '{}' instance. Use the base URL {} instead of https://domain.freshservice.com in examples.\n",
+                name, base_url.trim_end_matches('/')
+            ))
+        })
+    }
+    
+    /// API version and recent changes, for queries that ask about versions or the changelog.
+    pub fn version_context(&self, query: &str) -> Option<String> {
+        const MAX_CHANGELOG_ENTRIES: usize = 5;
+        
+        let query_lower = query.to_lowercase();
+        if !["version", "changelog", "release", "what's new"].iter().any(|term| query_lower.contains(term)) {
+            return None;
+        }
+        
+        let docs = &self.documentation;
+        if docs.api_version.is_none() && docs.changelog.is_empty() {
+            return None;
+        }
+        
+        let mut context = String::new();
+        if let Some(version) = &docs.api_version {
+            context.push_str(&format!("API version: {}\n", version));
+        }
+        if !docs.changelog.is_empty() {
+            context.push_str("Recent changes:\n");
+            for entry in docs.changelog.iter().take(MAX_CHANGELOG_ENTRIES) {
+                context.push_str(&format!("  - {}\n", entry.description));
+            }
+        }
+        Some(context)
     }
     
     pub fn calculate_confidence(&self, query: &str, matches: &[(&ApiEndpoint, f32)]) -> f32 {
@@ -176,22 +725,62 @@ impl RagPipeline {
         (length_score * 0.6 + term_score * 0.4).min(1.0)
     }
     
+    /// Looks up an endpoint by name or alias, ignoring case.
+    pub fn find_endpoint(&self, name: &str) -> Option<&ApiEndpoint> {
+        self.documentation.endpoints.iter().find(|endpoint| {
+            std::iter::once(&endpoint.name)
+                .chain(&endpoint.aliases)
+                .any(|candidate| candidate.eq_ignore_ascii_case(name))
+        })
+    }
+    
     pub fn get_documentation(&self) -> &ScrapedDocumentation {
         &self.documentation
     }
     
+    pub fn documentation_hash(&self) -> &str {
+        &self.documentation_hash
+    }
+    
     // Helper method to get top N endpoints
     pub fn get_top_matches(&self, query: &str, limit: usize) -> Vec<(&ApiEndpoint, f32)> {
-        let mut matches = self.find_relevant_endpoints(query);
+        let mut matches = self.ranked_endpoints(query);
         matches.truncate(limit);
         matches
     }
+    
+    /// Ranked endpoint lookup without any LLM involvement.
+    pub fn search(&self, query: &str, limit: usize) -> Vec<SearchResult> {
+        let tokens = self.tokenizer.tokenize(query);
+        let terms: Vec<&str> = tokens.iter().map(String::as_str).collect();
+        
+        self.get_top_matches(query, limit)
+            .into_iter()
+            .map(|(endpoint, score)| SearchResult {
+                name: endpoint.name.clone(),
+                method: endpoint.method,
+                path: endpoint.path.clone(),
+                score,
+                snippet: extract_snippet(&endpoint.description, &terms, SNIPPET_WINDOW),
+            })
+            .collect()
+    }
+}
+
+/// Share of distinct words two sets have in common, from 0.0 (disjoint) to 1.0 (identical).
+fn jaccard(a: &std::collections::HashSet<String>, b: &std::collections::HashSet<String>) -> f32 {
+    let union = a.union(b).count();
+    if union == 0 {
+        return 0.0;
+    }
+    a.intersection(b).count() as f32 / union as f32
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::models::{ApiParameter};
+    use crate::models::ParamType;
+    use crate::rag::HashingEmbedder;
     
     #[test]
     fn test_calculate_relevance_score() {
@@ -199,14 +788,59 @@ mod tests {
         let endpoint = &pipeline.documentation.endpoints[0];
         
         // Should match "create ticket"
-        let score = pipeline.calculate_relevance_score(endpoint, "create ticket");
+        let score = pipeline.calculate_relevance_score(endpoint, "create ticket", None);
         assert!(score > 0.5);
         
         // Should not match unrelated query
-        let score = pipeline.calculate_relevance_score(endpoint, "delete user");
+        let score = pipeline.calculate_relevance_score(endpoint, "delete user", None);
         assert!(score < 0.3);
     }
     
+    #[test]
+    fn test_stemmed_query_terms_match_plural_text() {
+        let pipeline = create_test_pipeline();
+        let endpoint = ApiEndpoint {
+            name: "List Time Entries".to_string(),
+            description: "Lists the time entries of a ticket".to_string(),
+            ..Default::default()
+        };
+        
+        // "entries" is stemmed to "entry", which still counts against the plural name and description
+        let breakdown = pipeline.score_breakdown(&endpoint, "list time entries");
+        assert_eq!(breakdown.name, pipeline.weights.name * (2.0 + 3.0 * 0.5) / 2.5);
+        assert_eq!(breakdown.description, pipeline.weights.description * (3.0 * 0.3 + 3.0 * 0.3) / 1.3);
+    }
+    
+    #[test]
+    fn test_phrase_match_beats_scattered_words() {
+        let pipeline = RagPipeline::new(ScrapedDocumentation::sample(&[
+            ("Time Log for Audit Entry", HttpMethod::Get, "/api/v2/example", "Export the audit trail"),
+            ("View a Time Entry", HttpMethod::Get, "/api/v2/example", "Returns a single record"),
+        ]));
+
+        let matches = pipeline.find_relevant_endpoints("how to log time entries");
+        assert_eq!(matches[0].0.name, "View a Time Entry");
+        assert!(matches[0].1 > matches[1].1);
+    }
+    
+    #[test]
+    fn test_same_path_endpoints_order_by_method_without_intent() {
+        let pipeline = RagPipeline::new(ScrapedDocumentation::sample(&[
+            ("Remove Tickets", HttpMethod::Delete, "/api/v2/tickets", "Bulk removal of tickets"),
+            ("Modify Tickets", HttpMethod::Put, "/api/v2/tickets", "Bulk update"),
+            ("Tickets", HttpMethod::Post, "/api/v2/tickets", "Opens a ticket"),
+            ("All Tickets", HttpMethod::Get, "/api/v2/tickets", "Every ticket"),
+        ]));
+        
+        let methods = |query: &str| -> Vec<HttpMethod> {
+            pipeline.find_relevant_endpoints(query).iter().map(|(endpoint, _)| endpoint.method).collect()
+        };
+        assert_eq!(methods("/api/v2/tickets"), vec![HttpMethod::Get, HttpMethod::Post, HttpMethod::Put, HttpMethod::Delete]);
+        
+        // An explicit intent still decides
+        assert_eq!(methods("remove /api/v2/tickets")[0], HttpMethod::Delete);
+    }
+    
     #[test]
     fn test_find_relevant_endpoints() {
         let pipeline = create_test_pipeline();
@@ -216,23 +850,520 @@ mod tests {
         assert!(matches[0].1 > 0.0);
     }
     
+    #[test]
+    fn test_score_floor_and_max_returned_trim_the_tail() {
+        let documentation = ScrapedDocumentation::sample(&[
+            ("Create Ticket", HttpMethod::Post, "/api/v2/example", "Create a new ticket"),
+            ("Create Asset", HttpMethod::Post, "/api/v2/example", "Create a new asset"),
+            ("Ticket Fields", HttpMethod::Post, "/api/v2/example", "Custom fields on requests"),
+        ]);
+        let scores: Vec<f32> = RagPipeline::new(documentation.clone())
+            .find_relevant_endpoints("create a ticket")
+            .iter()
+            .map(|(_, score)| *score)
+            .collect();
+        assert_eq!(scores.len(), 3);
+        assert!(scores[1] > scores[2]);
+        
+        let floor = (scores[1] + scores[2]) / 2.0;
+        let pipeline = RagPipeline::new(documentation.clone())
+            .with_weights(ScoringWeights { score_floor: floor, ..ScoringWeights::default() });
+        let matches = pipeline.find_relevant_endpoints("create a ticket");
+        assert_eq!(matches.len(), 2);
+        assert!(matches.iter().all(|(_, score)| *score > floor));
+        
+        let pipeline = RagPipeline::new(documentation)
+            .with_weights(ScoringWeights { max_returned: 1, ..ScoringWeights::default() });
+        let matches = pipeline.find_relevant_endpoints("create a ticket");
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].0.name, "Create Ticket");
+        assert_eq!(pipeline.search("create a ticket", 10).len(), 3);
+    }
+    
+    /// Hashing embedder that counts how often it is asked for a vector.
+    #[derive(Clone, Default)]
+    struct CountingEmbedder {
+        calls: Arc<std::sync::atomic::AtomicUsize>,
+    }
+    
+    impl Embedder for CountingEmbedder {
+        fn embed(&self, text: &str) -> Vec<f32> {
+            self.calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            HashingEmbedder::default().embed(text)
+        }
+        
+        fn id(&self) -> String {
+            HashingEmbedder::default().id()
+        }
+    }
+    
+    #[test]
+    fn test_query_is_embedded_once_per_retrieval() {
+        let pipeline = RagPipeline::new(ScrapedDocumentation::sample(&[
+            ("Create Ticket", HttpMethod::Get, "/api/v2/create_ticket", ""),
+            ("Ticket Fields", HttpMethod::Get, "/api/v2/ticket_fields", ""),
+            ("Ticket Tasks", HttpMethod::Get, "/api/v2/ticket_tasks", ""),
+        ]));
+        let mut cache = EmbeddingCache::default();
+        cache.refresh(&pipeline.documentation.endpoints, &HashingEmbedder::default());
+        let embedder = CountingEmbedder::default();
+        let pipeline = pipeline.with_embeddings(cache, Box::new(embedder.clone()), 0.1);
+        
+        let matches = pipeline.find_relevant_endpoints("ticket");
+        
+        assert_eq!(matches.len(), 3);
+        assert_eq!(embedder.calls.load(std::sync::atomic::Ordering::SeqCst), 1);
+        assert!(pipeline.score_breakdown(matches[0].0, "ticket").semantic > 0.0);
+    }
+    
+    #[test]
+    fn test_feedback_boost_breaks_tie() {
+        let documentation = ScrapedDocumentation::sample(&[
+            ("Archive A", HttpMethod::Put, "/api/v2/tickets/{id}/archive", "Archive a ticket"),
+            ("Archive B", HttpMethod::Put, "/api/v2/tickets/{id}/archive", "Archive a ticket"),
+        ]);
+        let entries = vec![crate::rag::FeedbackEntry {
+            query: "archive ticket".to_string(),
+            helpful: true,
+            endpoints: vec!["Archive B".to_string()],
+            recorded_at: chrono::Utc::now(),
+        }];
+        let boosts = FeedbackBoosts::from_entries(&entries, &Default::default());
+        
+        let unboosted = RagPipeline::new(documentation.clone());
+        let plain = unboosted.find_relevant_endpoints("archive ticket");
+        assert_eq!(plain[0].1, plain[1].1);
+        
+        let boosted = RagPipeline::new(documentation).with_feedback_boosts(boosts);
+        let matches = boosted.find_relevant_endpoints("archive ticket");
+        assert_eq!(matches[0].0.name, "Archive B");
+        assert!(matches[0].1 > matches[1].1);
+    }
+    
+    #[test]
+    fn test_intent_boosts_matching_method() {
+        let pipeline = RagPipeline::new(ScrapedDocumentation::sample(&[
+            ("Ticket A", HttpMethod::Get, "/api/v2/tickets/{id}", "Ticket operation"),
+            ("Ticket B", HttpMethod::Put, "/api/v2/tickets/{id}", "Ticket operation"),
+            ("Ticket C", HttpMethod::Delete, "/api/v2/tickets/{id}", "Ticket operation"),
+            ("Ticket D", HttpMethod::Post, "/api/v2/tickets/{id}", "Ticket operation"),
+        ]));
+        
+        for (query, method) in [
+            ("remove ticket", HttpMethod::Delete),
+            ("modify ticket", HttpMethod::Put),
+            ("show ticket", HttpMethod::Get),
+            ("create ticket", HttpMethod::Post),
+        ] {
+            let matches = pipeline.find_relevant_endpoints(query);
+            assert_eq!(matches[0].0.method, method, "{}", query);
+            assert!(pipeline.score_breakdown(matches[0].0, query).method > 0.0);
+            assert_eq!(pipeline.score_breakdown(matches[1].0, query).method, 0.0);
+        }
+    }
+    
+    #[test]
+    fn test_update_intent_boosts_patch_endpoints() {
+        let pipeline = RagPipeline::new(ScrapedDocumentation::sample(&[
+            ("Ticket A", HttpMethod::Get, "/api/v2/tickets/{id}", "Ticket operation"),
+            ("Ticket B", HttpMethod::Patch, "/api/v2/tickets/{id}", "Ticket operation"),
+        ]));
+        
+        let matches = pipeline.find_relevant_endpoints("patch ticket");
+        assert_eq!(matches[0].0.method, HttpMethod::Patch);
+        assert!(pipeline.score_breakdown(matches[0].0, "update ticket").method > 0.0);
+    }
+    
+    #[test]
+    fn test_blocked_endpoint_never_matches() {
+        let mut documentation = create_test_pipeline().documentation;
+        documentation.endpoints.push(ApiEndpoint {
+            name: "Delete Ticket".to_string(),
+            description: "Delete a ticket".to_string(),
+            method: HttpMethod::Delete,
+            path: "/api/v2/tickets/{id}".to_string(),
+            ..Default::default()
+        });
+        let open = RagPipeline::new(documentation.clone());
+        assert_eq!(open.find_relevant_endpoints("delete ticket")[0].0.name, "Delete Ticket");
+        
+        let blocked = RagPipeline::new(documentation)
+            .with_blocklist(&Blocklist::new(&["DELETE /api/v2/tickets/*".to_string()]).unwrap());
+        
+        for query in ["delete ticket", "Delete Ticket", "DELETE /api/v2/tickets/{id}"] {
+            assert!(blocked.find_relevant_endpoints(query).iter().all(|(e, _)| e.name != "Delete Ticket"));
+        }
+        assert!(blocked.search("delete ticket", 10).iter().all(|r| r.name != "Delete Ticket"));
+        assert!(blocked.find_endpoint("Delete Ticket").is_none());
+        assert_ne!(blocked.documentation_hash(), open.documentation_hash());
+    }
+    
+    struct NicknameTokenizer;
+    
+    impl Tokenizer for NicknameTokenizer {
+        fn tokenize(&self, text: &str) -> Vec<String> {
+            text.split_whitespace()
+                .map(|word| if word == "tix" { "ticket".to_string() } else { word.to_string() })
+                .collect()
+        }
+    }
+    
+    #[test]
+    fn test_custom_tokenizer_drives_scoring() {
+        let default = create_test_pipeline();
+        let endpoint = &default.documentation.endpoints[0];
+        let plain = default.score_breakdown(endpoint, "tix");
+        
+        let custom = create_test_pipeline().with_tokenizer(Box::new(NicknameTokenizer));
+        let nicknamed = custom.score_breakdown(endpoint, "tix");
+        
+        assert_eq!(plain.name, 0.0);
+        assert!(nicknamed.name > 0.0);
+        assert!(nicknamed.description > 0.0);
+    }
+    
+    #[test]
+    fn test_response_fields_from_sample_response() {
+        let endpoint = ApiEndpoint {
+            name: "View Ticket".to_string(),
+            response_schema: Some(serde_json::json!({
+                "ticket": {"id": 1, "subject": "Printer on fire", "priority": 2, "cc_emails": [], "is_escalated": false, "custom_fields": {}, "due_by": null, "score": 1.5}
+            })),
+            ..Default::default()
+        };
+        
+        let fields = RagPipeline::response_fields(&endpoint);
+        let field = |name: &str| fields.iter().find(|(n, _)| n == name).map(|(_, t)| t.as_str());
+        
+        assert_eq!(fields.len(), 8);
+        assert_eq!(field("id"), Some("integer"));
+        assert_eq!(field("subject"), Some("string"));
+        assert_eq!(field("cc_emails"), Some("array"));
+        assert_eq!(field("is_escalated"), Some("boolean"));
+        assert_eq!(field("custom_fields"), Some("object"));
+        assert_eq!(field("due_by"), Some("null"));
+        assert_eq!(field("score"), Some("number"));
+        
+        let list = ApiEndpoint {
+            response_schema: Some(serde_json::json!({"tickets": [{"id": 1}]})),
+            ..Default::default()
+        };
+        assert_eq!(RagPipeline::response_fields(&list), vec![("id".to_string(), "integer".to_string())]);
+        assert!(RagPipeline::response_fields(&ApiEndpoint::default()).is_empty());
+        
+        let pipeline = create_test_pipeline();
+        let matches = [(&endpoint, 1.0)];
+        assert!(pipeline.response_context("what fields does a ticket response have", &matches).unwrap().contains("  - subject (string)"));
+        assert!(pipeline.response_context("create ticket", &matches).is_none());
+    }
+    
+    #[test]
+    fn test_environment_context() {
+        let pipeline = create_test_pipeline().with_environments(BTreeMap::from([
+            ("production".to_string(), "https://acme.freshservice.com".to_string()),
+            ("sandbox".to_string(), "https://acme-sandbox.freshservice.com/".to_string()),
+        ]));
+        
+        let by_name = pipeline.environment_context("create a ticket in Sandbox").unwrap();
+        assert!(by_name.contains("'sandbox'"));
+        assert!(by_name.contains("https://acme-sandbox.freshservice.com instead"));
+        
+        assert!(pipeline.environment_context("create a ticket on acme-sandbox").unwrap().contains("'sandbox'"));
+        assert!(pipeline.environment_context("post to acme.freshservice.com/api/v2/tickets").unwrap().contains("'production'"));
+        assert_eq!(pipeline.environment_context("create a ticket"), None);
+    }
+    
+    #[test]
+    fn test_alias_match_surfaces_endpoint() {
+        let mut pipeline = create_test_pipeline();
+        pipeline.documentation.endpoints.push(ApiEndpoint {
+            name: "Raise Ticket".to_string(),
+            description: "Raise a ticket on behalf of a requester".to_string(),
+            method: HttpMethod::Post,
+            path: "/api/v2/tickets".to_string(),
+            ..Default::default()
+        });
+        let without_alias = pipeline.calculate_relevance_score(&pipeline.documentation.endpoints[1], "open ticket", None);
+        pipeline.documentation.endpoints[1].aliases = vec!["Open Ticket".to_string()];
+        let with_alias = pipeline.calculate_relevance_score(&pipeline.documentation.endpoints[1], "open ticket", None);
+        pipeline.rebuild_index();
+        
+        let matches = pipeline.find_relevant_endpoints("open ticket");
+        
+        assert!(with_alias > without_alias);
+        assert_eq!(matches[0].0.name, "Raise Ticket");
+    }
+    
+    #[test]
+    fn test_search_ranks_best_match_first() {
+        let mut pipeline = create_test_pipeline();
+        pipeline.documentation.endpoints.push(ApiEndpoint {
+            name: "Delete Ticket".to_string(),
+            description: "Delete a ticket. Deleted tickets can be restored from the trash.".to_string(),
+            method: HttpMethod::Delete,
+            path: "/api/v2/tickets/{id}".to_string(),
+            ..Default::default()
+        });
+        pipeline.rebuild_index();
+        
+        let results = pipeline.search("delete ticket", 10);
+        
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].name, "Delete Ticket");
+        assert_eq!(results[0].method, HttpMethod::Delete);
+        assert!(results[0].score > results[1].score);
+        
+        assert_eq!(pipeline.search("delete ticket", 1).len(), 1);
+    }
+    
+    #[test]
+    fn test_status_code_query_surfaces_endpoint() {
+        let mut pipeline = create_test_pipeline();
+        pipeline.documentation.endpoints[0].status_codes = vec![201];
+        pipeline.documentation.endpoints.push(ApiEndpoint {
+            name: "Delete Ticket".to_string(),
+            description: "Delete a ticket".to_string(),
+            method: HttpMethod::Delete,
+            path: "/api/v2/tickets/{id}".to_string(),
+            status_codes: vec![204],
+            ..Default::default()
+        });
+        pipeline.rebuild_index();
+        
+        let matches = pipeline.find_relevant_endpoints("which ticket endpoint returns 204");
+        let with_status: Vec<_> = pipeline.endpoints_with_status(204).iter().map(|e| e.name.as_str()).collect();
+        
+        assert_eq!(matches[0].0.name, "Delete Ticket");
+        assert_eq!(with_status, vec!["Delete Ticket"]);
+        assert!(pipeline.endpoints_with_status(500).is_empty());
+    }
+    
+    #[test]
+    fn test_format_context_stops_at_score_cliff() {
+        let endpoints: Vec<ApiEndpoint> = ["Create Ticket", "Update Ticket", "List Agents", "List Assets"]
+            .iter()
+            .map(|name| ApiEndpoint {
+                name: name.to_string(),
+                description: format!("{} endpoint", name),
+                ..Default::default()
+            })
+            .collect();
+        let pipeline = create_test_pipeline();
+        let matches = vec![
+            (&endpoints[0], 0.9),
+            (&endpoints[1], 0.85),
+            (&endpoints[2], 0.3),
+            (&endpoints[3], 0.29),
+        ];
+        
+        let (context, max_score) = pipeline.format_context(&matches);
+        
+        assert_eq!(max_score, 0.9);
+        assert!(context.contains("Create Ticket") && context.contains("Update Ticket"));
+        assert!(!context.contains("List Agents") && !context.contains("List Assets"));
+    }
+    
+    #[test]
+    fn test_format_context_explains_pagination() {
+        let endpoint = ApiEndpoint {
+            name: "List Tickets".to_string(),
+            path: "/api/v2/tickets".to_string(),
+            pagination: Some(crate::models::PaginationInfo {
+                max_per_page: Some(100),
+                link_header: Some("The Link header points at the next page.".to_string()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        
+        let (context, _) = create_test_pipeline().format_context(&[(&endpoint, 0.9)]);
+        
+        assert!(context.contains(
+            "Pagination: page-based (`page` and `per_page` query parameters), at most 100 per page. The Link header points at the next page.\n"
+        ));
+    }
+    
+    #[test]
+    fn test_format_context_includes_notes() {
+        let endpoint = ApiEndpoint {
+            name: "Delete Ticket".to_string(),
+            path: "/api/v2/tickets/{id}".to_string(),
+            notes: vec!["Deleting a ticket is irreversible.".to_string()],
+            ..Default::default()
+        };
+        
+        let (context, _) = create_test_pipeline().format_context(&[(&endpoint, 0.9)]);
+        
+        assert!(context.contains("Notes:\n  - Deleting a ticket is irreversible.\n"));
+    }
+    
+    #[test]
+    fn test_format_context_collapses_shared_descriptions() {
+        let boilerplate = "Manage asset records of your service desk account";
+        let endpoints: Vec<ApiEndpoint> = [
+            ("List Assets", HttpMethod::Get, "/api/v2/assets", boilerplate.to_string()),
+            ("Create Asset", HttpMethod::Post, "/api/v2/assets", format!("{}.", boilerplate)),
+            ("Delete Asset", HttpMethod::Delete, "/api/v2/assets/{id}", "Manage the asset records of your service desk account".to_string()),
+            ("Create Ticket", HttpMethod::Post, "/api/v2/tickets", "Create a new ticket".to_string()),
+        ]
+        .into_iter()
+        .map(|(name, method, path, description)| ApiEndpoint {
+            name: name.to_string(),
+            method,
+            path: path.to_string(),
+            description,
+            ..Default::default()
+        })
+        .collect();
+        let matches: Vec<_> = endpoints.iter().map(|e| (e, 0.8)).collect();
+        
+        let (context, _) = create_test_pipeline().format_context(&matches);
+        
+        assert!(context.starts_with(&format!("Shared description (List Assets, Create Asset, Delete Asset): {}\n", boilerplate)));
+        assert_eq!(context.matches("Description: see shared description above").count(), 3);
+        assert_eq!(context.matches("service desk account").count(), 1);
+        assert!(context.contains("Description: Create a new ticket"));
+        assert!(context.contains("Path: /api/v2/assets/{id}"));
+    }
+    
+    #[test]
+    fn test_format_context_respects_token_budget() {
+        let endpoints: Vec<ApiEndpoint> = (0..3)
+            .map(|i| ApiEndpoint {
+                name: format!("Endpoint {}", i),
+                description: "x".repeat(200),
+                ..Default::default()
+            })
+            .collect();
+        let pipeline = create_test_pipeline().with_context_config(ContextConfig {
+            token_budget: 100,
+            ..Default::default()
+        });
+        let matches: Vec<_> = endpoints.iter().map(|e| (e, 0.8)).collect();
+        
+        let (context, _) = pipeline.format_context(&matches);
+        
+        assert!(context.contains("Endpoint 0"));
+        assert!(!context.contains("Endpoint 1"));
+    }
+    
+    #[test]
+    fn test_version_context() {
+        let mut pipeline = create_test_pipeline();
+        assert_eq!(pipeline.version_context("which api version is this?"), None);
+        
+        pipeline.documentation.api_version = Some("v2".to_string());
+        
+        assert_eq!(pipeline.version_context("which API version is this?").as_deref(), Some("API version: v2\n"));
+        assert_eq!(pipeline.version_context("create a ticket"), None);
+    }
+    
+    /// A catalog of `size` endpoints across many made-up resources, for comparing the indexed
+    /// lookup against a full scan.
+    fn synthetic_pipeline(size: usize) -> RagPipeline {
+        const RESOURCES: [&str; 12] = [
+            "ticket", "problem", "change", "release", "asset", "agent",
+            "requester", "department", "location", "vendor", "contract", "software",
+        ];
+        const METHODS: [HttpMethod; 4] = [HttpMethod::Get, HttpMethod::Post, HttpMethod::Put, HttpMethod::Delete];
+        let endpoints = (0..size)
+            .map(|i| {
+                let resource = format!("{}{}", RESOURCES[i % RESOURCES.len()], i / RESOURCES.len());
+                let method = METHODS[i % METHODS.len()];
+                ApiEndpoint {
+                    name: format!("{} {}", method, resource),
+                    description: format!("Manage the {} records of an account", resource),
+                    method,
+                    path: format!("/api/v2/{}s", resource),
+                    status_codes: vec![if method == HttpMethod::Delete { 204 } else { 200 }],
+                    curl_example: (i % 3 == 0).then(|| format!("curl -X {} /api/v2/{}s", method, resource)),
+                    ..Default::default()
+                }
+            })
+            .collect();
+        RagPipeline::new(ScrapedDocumentation { endpoints, ..Default::default() })
+    }
+    
+    /// Scores every endpoint, as retrieval did before the inverted index.
+    fn scan_relevant_endpoints<'a>(pipeline: &'a RagPipeline, query: &str) -> Vec<(&'a ApiEndpoint, f32)> {
+        let query_lower = query.to_lowercase();
+        let mut matches: Vec<_> = pipeline.documentation.endpoints
+            .iter()
+            .map(|endpoint| (endpoint, pipeline.calculate_relevance_score(endpoint, &query_lower, None)))
+            .filter(|(_, score)| *score > pipeline.weights.score_floor)
+            .collect();
+        matches.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        matches.truncate(pipeline.weights.max_returned);
+        matches
+    }
+    
+    const SAMPLE_QUERIES: [&str; 9] = [
+        "delete vendor7",
+        "how do I create a ticket",
+        "which endpoint returns 204",
+        "curl example for asset12",
+        "manage records",
+        "tick",
+        "vendor7 api",
+        "department40 records",
+        "software3",
+    ];
+    
+    #[test]
+    fn test_indexed_retrieval_matches_full_scan() {
+        let pipeline = synthetic_pipeline(500);
+        
+        for query in SAMPLE_QUERIES {
+            let indexed: Vec<_> = pipeline.find_relevant_endpoints(query).iter().map(|(e, s)| (e.name.clone(), *s)).collect();
+            let scanned: Vec<_> = scan_relevant_endpoints(&pipeline, query).iter().map(|(e, s)| (e.name.clone(), *s)).collect();
+            assert_eq!(indexed, scanned, "query: {}", query);
+        }
+    }
+    
+    /// Timing comparison; run with `cargo test --release bench_indexed -- --ignored --nocapture`.
+    #[test]
+    #[ignore]
+    fn bench_indexed_vs_scan_retrieval() {
+        let pipeline = synthetic_pipeline(20_000);
+        let rounds = 20;
+        
+        let start = std::time::Instant::now();
+        for _ in 0..rounds {
+            for query in SAMPLE_QUERIES {
+                std::hint::black_box(scan_relevant_endpoints(&pipeline, query));
+            }
+        }
+        let scan = start.elapsed();
+        
+        let start = std::time::Instant::now();
+        for _ in 0..rounds {
+            for query in SAMPLE_QUERIES {
+                std::hint::black_box(pipeline.find_relevant_endpoints(query));
+            }
+        }
+        let indexed = start.elapsed();
+        
+        println!("20000 endpoints, {} queries: scan {:?}, indexed {:?}", rounds * SAMPLE_QUERIES.len(), scan, indexed);
+    }
+    
     fn create_test_pipeline() -> RagPipeline {
         let endpoints = vec![
             ApiEndpoint {
                 name: "Create Ticket".to_string(),
                 description: "Create a new ticket".to_string(),
-                method: "POST".to_string(),
+                method: HttpMethod::Post,
                 path: "/api/v2/tickets".to_string(),
                 parameters: vec![
                     ApiParameter {
                         name: "subject".to_string(),
-                        param_type: "string".to_string(),
+                        param_type: ParamType::String,
                         description: "Ticket subject".to_string(),
                         required: true,
-                        default: None,
+                        ..Default::default()
                     }
                 ],
                 curl_example: Some("curl -X POST ...".to_string()),
+                ..Default::default()
             }
         ];
         
@@ -240,6 +1371,7 @@ mod tests {
             base_url: "https://api.freshservice.com".to_string(),
             endpoints,
             scraped_at: chrono::Utc::now(),
+            ..Default::default()
         })
     }
 }
\ No newline at end of file
@@ -0,0 +1,117 @@
+use super::RagPipeline;
+use crate::models::{ApiEndpoint, ScrapedDocumentation};
+use anyhow::{Context, Result};
+use std::path::Path;
+
+/// An endpoint matched by [`RagRouter`], tagged with the documentation set it came from.
+#[derive(Debug, Clone, Copy)]
+pub struct RoutedMatch<'a> {
+    pub source: &'a str,
+    pub endpoint: &'a ApiEndpoint,
+    pub score: f32,
+}
+
+/// Several named documentation sets (e.g. `tickets`, `assets`) searched as one. Each set keeps
+/// its own pipeline; a query runs against all of them and the matches are merged by score.
+#[derive(Clone, Default)]
+pub struct RagRouter {
+    pipelines: Vec<(String, RagPipeline)>,
+}
+
+impl RagRouter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_pipeline(mut self, name: impl Into<String>, pipeline: RagPipeline) -> Self {
+        self.pipelines.push((name.into(), pipeline));
+        self
+    }
+
+    /// One set per `*.json` file in `dir`, named after the file stem and built by `build`.
+    /// Files load in name order so ties between sets rank the same on every start.
+    pub fn from_dir(dir: &Path, build: impl Fn(ScrapedDocumentation) -> Result<RagPipeline>) -> Result<Self> {
+        let mut files: Vec<_> = std::fs::read_dir(dir)
+            .with_context(|| format!("Failed to read docs directory {}", dir.display()))?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.extension().is_some_and(|extension| extension == "json"))
+            .collect();
+        files.sort();
+        if files.is_empty() {
+            anyhow::bail!("No documentation files (*.json) found in {}", dir.display());
+        }
+
+        files.iter().try_fold(Self::new(), |router, path| {
+            let name = path.file_stem().unwrap_or_default().to_string_lossy().into_owned();
+            let pipeline = build(ScrapedDocumentation::from_file(path)?)?;
+            println!("Loaded documentation set '{}': {} endpoints", name, pipeline.get_documentation().endpoints.len());
+            Ok(router.with_pipeline(name, pipeline))
+        })
+    }
+
+    pub fn sources(&self) -> impl Iterator<Item = &str> {
+        self.pipelines.iter().map(|(name, _)| name.as_str())
+    }
+
+    /// Matches from every set, best first. Scores are normalized per pipeline with the same
+    /// weights, so they compare directly; ties keep the sets' load order.
+    pub fn find_relevant_endpoints(&self, query: &str) -> Vec<RoutedMatch<'_>> {
+        let mut matches: Vec<RoutedMatch<'_>> = self.pipelines
+            .iter()
+            .flat_map(|(source, pipeline)| {
+                pipeline.find_relevant_endpoints(query)
+                    .into_iter()
+                    .map(move |(endpoint, score)| RoutedMatch { source, endpoint, score })
+            })
+            .collect();
+        matches.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+        matches
+    }
+
+    /// All sets as one catalog, for routes that list or look up endpoints regardless of source.
+    pub fn merged_documentation(&self) -> ScrapedDocumentation {
+        let mut merged = ScrapedDocumentation::default();
+        for (_, pipeline) in &self.pipelines {
+            let documentation = pipeline.get_documentation();
+            if merged.base_url.is_empty() {
+                merged.base_url = documentation.base_url.clone();
+                merged.api_version = documentation.api_version.clone();
+                merged.changelog = documentation.changelog.clone();
+            }
+            merged.scraped_at = merged.scraped_at.max(documentation.scraped_at);
+            merged.endpoints.extend(documentation.endpoints.iter().cloned());
+            merged.js_rendered_sections.extend(documentation.js_rendered_sections.iter().cloned());
+        }
+        merged
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::HttpMethod;
+
+    #[test]
+    fn test_merges_and_tags_matches_across_sets() {
+        let router = RagRouter::new()
+            .with_pipeline("tickets", RagPipeline::new(ScrapedDocumentation::sample(&[
+                ("Create Ticket", HttpMethod::Post, "/api/v2/tickets", "Create a new ticket"),
+                ("Delete Ticket", HttpMethod::Delete, "/api/v2/tickets/{id}", "Delete a ticket"),
+            ])))
+            .with_pipeline("assets", RagPipeline::new(ScrapedDocumentation::sample(&[
+                ("Create Asset", HttpMethod::Post, "/api/v2/assets", "Create a new asset"),
+            ])));
+
+        let asset_matches = router.find_relevant_endpoints("create a new asset");
+        assert_eq!(asset_matches[0].source, "assets");
+        assert_eq!(asset_matches[0].endpoint.name, "Create Asset");
+        assert!(asset_matches.iter().any(|m| m.source == "tickets"));
+        assert!(asset_matches.windows(2).all(|pair| pair[0].score >= pair[1].score));
+
+        let ticket_matches = router.find_relevant_endpoints("delete ticket");
+        assert_eq!((ticket_matches[0].source, ticket_matches[0].endpoint.name.as_str()), ("tickets", "Delete Ticket"));
+
+        assert_eq!(router.sources().collect::<Vec<_>>(), vec!["tickets", "assets"]);
+        assert_eq!(router.merged_documentation().endpoints.len(), 3);
+    }
+}
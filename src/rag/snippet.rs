@@ -0,0 +1,99 @@
+/// Returns about `window` characters of `text` centered on the query terms, preferring the
+/// window that covers the most distinct terms. Cut edges are trimmed to whole words and marked
+/// with `...`. When no term occurs, the leading window is returned.
+pub fn extract_snippet(text: &str, query_terms: &[&str], window: usize) -> String {
+    let text = text.trim();
+    let chars: Vec<char> = text.chars().collect();
+    if chars.len() <= window {
+        return text.to_string();
+    }
+
+    // Lowercase char by char so indices line up with `chars`
+    let lower: Vec<char> = chars.iter().map(|c| c.to_lowercase().next().unwrap_or(*c)).collect();
+    let terms: Vec<Vec<char>> = query_terms.iter()
+        .map(|term| term.to_lowercase().chars().collect::<Vec<char>>())
+        .filter(|term| !term.is_empty())
+        .collect();
+    let occurrences: Vec<(usize, usize)> = terms.iter()
+        .enumerate()
+        .flat_map(|(t, term)| {
+            lower.windows(term.len())
+                .enumerate()
+                .filter(move |(_, candidate)| candidate == term)
+                .map(move |(i, _)| (i, t))
+        })
+        .collect();
+
+    let max_start = chars.len() - window;
+    let start = occurrences.iter()
+        .map(|(i, t)| {
+            let center = i + terms[*t].len() / 2;
+            let start = center.saturating_sub(window / 2).min(max_start);
+            let mut covered: Vec<usize> = occurrences.iter()
+                .filter(|(j, u)| *j >= start && j + terms[*u].len() <= start + window)
+                .map(|(_, u)| *u)
+                .collect();
+            covered.sort_unstable();
+            covered.dedup();
+            (start, covered.len())
+        })
+        // Most distinct terms wins; among equals, the earliest window
+        .max_by(|a, b| a.1.cmp(&b.1).then(b.0.cmp(&a.0)))
+        .map(|(start, _)| start)
+        .unwrap_or(0);
+    let mut end = start + window;
+    let mut start = start;
+
+    // Don't cut words in half at either edge
+    if start > 0
+        && let Some(space) = chars[start..end].iter().position(|c| c.is_whitespace())
+    {
+        start += space + 1;
+    }
+    if end < chars.len()
+        && let Some(space) = chars[start..end].iter().rposition(|c| c.is_whitespace())
+    {
+        end = start + space;
+    }
+
+    let body: String = chars[start..end].iter().collect();
+    format!(
+        "{}{}{}",
+        if start > 0 { "..." } else { "" },
+        body.trim(),
+        if end < chars.len() { "..." } else { "" },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEXT: &str = "Tickets are the core object of the service desk. Use this endpoint to restore \
+        a deleted ticket by its id so that agents can continue working on it. Restored tickets keep \
+        their conversation history, tasks and time entries.";
+
+    #[test]
+    fn test_window_centers_on_terms() {
+        let snippet = extract_snippet(TEXT, &["restore", "deleted"], 60);
+
+        assert!(snippet.starts_with("..."));
+        assert!(snippet.ends_with("..."));
+        assert!(snippet.contains("restore a deleted ticket"));
+        assert!(snippet.chars().count() <= 66);
+    }
+
+    #[test]
+    fn test_prefers_window_with_most_terms() {
+        // "tasks" appears alone near the end; "restore" and "agents" share a window in the middle
+        let snippet = extract_snippet(TEXT, &["tasks", "restore", "agents"], 100);
+
+        assert!(snippet.contains("restore") && snippet.contains("agents"));
+    }
+
+    #[test]
+    fn test_no_match_returns_leading_window() {
+        assert_eq!(extract_snippet(TEXT, &["webhook"], 30), "Tickets are the core object...");
+        assert_eq!(extract_snippet("Short description", &["unrelated"], 160), "Short description");
+    }
+}
@@ -0,0 +1,68 @@
+use super::tokenizer::EnglishTokenizer;
+use crate::models::{ApiEndpoint, HttpMethod};
+
+/// An example question a new user could ask about `endpoint`, built from its method and path:
+/// `POST /api/v2/tickets` gives "How do I create a ticket?". `None` for paths without a
+/// recognisable resource.
+pub fn question_for(endpoint: &ApiEndpoint) -> Option<String> {
+    let segments: Vec<&str> = endpoint.path
+        .trim_start_matches("/api/v2")
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .collect();
+    let targets_item = segments.last().is_some_and(|segment| segment.starts_with('{'));
+    let resources: Vec<&str> = segments.iter()
+        .copied()
+        .filter(|segment| !segment.starts_with('{'))
+        .collect();
+    let (last, parents) = resources.split_last()?;
+    
+    let mut singular: Vec<String> = parents.iter().map(|resource| resource_words(resource, true)).collect();
+    singular.push(resource_words(last, true));
+    let singular = singular.join(" ");
+    
+    let question = match endpoint.method {
+        HttpMethod::Post => format!("How do I create a {}?", singular),
+        HttpMethod::Get if targets_item => format!("How do I view a {} by ID?", singular),
+        HttpMethod::Get => {
+            let mut words: Vec<String> = parents.iter().map(|resource| resource_words(resource, true)).collect();
+            words.push(resource_words(last, false));
+            format!("List all {} with pagination", words.join(" "))
+        }
+        HttpMethod::Put | HttpMethod::Patch => format!("How do I update a {}?", singular),
+        HttpMethod::Delete => format!("How do I delete a {}?", singular),
+        HttpMethod::Head | HttpMethod::Options => return None,
+    };
+    Some(question)
+}
+
+/// `time_entries` as "time entry" (singular) or "time entries".
+fn resource_words(segment: &str, singular: bool) -> String {
+    let words = segment.replace(['_', '-'], " ");
+    if singular { EnglishTokenizer::stem(&words) } else { words }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn endpoint(method: HttpMethod, path: &str) -> ApiEndpoint {
+        ApiEndpoint { method, path: path.to_string(), ..Default::default() }
+    }
+    
+    #[test]
+    fn test_questions_follow_method_and_path() {
+        assert_eq!(question_for(&endpoint(HttpMethod::Post, "/api/v2/tickets")).unwrap(), "How do I create a ticket?");
+        assert_eq!(question_for(&endpoint(HttpMethod::Get, "/api/v2/tickets")).unwrap(), "List all tickets with pagination");
+        assert_eq!(question_for(&endpoint(HttpMethod::Get, "/api/v2/tickets/{id}")).unwrap(), "How do I view a ticket by ID?");
+        assert_eq!(
+            question_for(&endpoint(HttpMethod::Delete, "/api/v2/tickets/{id}/notes/{note_id}")).unwrap(),
+            "How do I delete a ticket note?"
+        );
+        assert_eq!(
+            question_for(&endpoint(HttpMethod::Get, "/api/v2/tickets/{id}/time_entries")).unwrap(),
+            "List all ticket time entries with pagination"
+        );
+        assert_eq!(question_for(&endpoint(HttpMethod::Get, "/api/v2")), None);
+    }
+}
@@ -0,0 +1,83 @@
+use crate::config::TokenizerKind;
+
+/// Splits text into the terms the scorer matches on. Swap implementations to adapt matching to
+/// another language or domain vocabulary.
+pub trait Tokenizer: Send + Sync {
+    fn tokenize(&self, text: &str) -> Vec<String>;
+}
+
+const STOPWORDS: &[&str] = &[
+    "a", "an", "and", "are", "can", "do", "does", "for", "how", "i", "in", "is", "it", "me", "my",
+    "of", "on", "or", "please", "the", "to", "what", "which", "with", "you",
+];
+
+/// Default tokenizer: lowercases, splits on anything but letters, digits and `_`, drops common
+/// English stopwords, and strips plural endings so "tickets" matches "ticket". Words ending in
+/// "ss", "us" or "is" ("address", "status", "analysis") are not plurals and stay whole, while
+/// their plurals lose the whole "es" ("statuses" → "status").
+#[derive(Debug, Clone, Copy, Default)]
+pub struct EnglishTokenizer;
+
+impl EnglishTokenizer {
+    pub(crate) fn stem(word: &str) -> String {
+        let singular_ending = |stem: &str| ["ss", "us", "is"].iter().any(|ending| stem.ends_with(ending));
+        if word.len() > 4 && word.ends_with("ies") {
+            format!("{}y", &word[..word.len() - 3])
+        } else if word.ends_with("es") && singular_ending(&word[..word.len() - 2]) {
+            word[..word.len() - 2].to_string()
+        } else if word.len() > 3 && word.ends_with('s') && !singular_ending(word) {
+            word[..word.len() - 1].to_string()
+        } else {
+            word.to_string()
+        }
+    }
+}
+
+impl Tokenizer for EnglishTokenizer {
+    fn tokenize(&self, text: &str) -> Vec<String> {
+        text.to_lowercase()
+            .split(|c: char| !c.is_alphanumeric() && c != '_')
+            .filter(|word| !word.is_empty() && !STOPWORDS.contains(word))
+            .map(Self::stem)
+            .collect()
+    }
+}
+
+/// Language-neutral tokenizer: lowercased whitespace-separated words, nothing dropped or stemmed.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct WhitespaceTokenizer;
+
+impl Tokenizer for WhitespaceTokenizer {
+    fn tokenize(&self, text: &str) -> Vec<String> {
+        text.to_lowercase().split_whitespace().map(str::to_string).collect()
+    }
+}
+
+/// The built-in tokenizer selected by `scoring.tokenizer`.
+pub fn tokenizer_for(kind: TokenizerKind) -> Box<dyn Tokenizer> {
+    match kind {
+        TokenizerKind::English => Box::new(EnglishTokenizer),
+        TokenizerKind::Whitespace => Box::new(WhitespaceTokenizer),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_english_tokenizer() {
+        assert_eq!(
+            EnglishTokenizer.tokenize("How do I list all Tickets' time_entries and categories?"),
+            vec!["list", "all", "ticket", "time_entry", "category"]
+        );
+        assert_eq!(EnglishTokenizer.tokenize("address status"), vec!["address", "status"]);
+        assert_eq!(EnglishTokenizer.tokenize("addresses statuses releases"), vec!["address", "status", "release"]);
+        assert!(EnglishTokenizer.tokenize("how do I").is_empty());
+    }
+
+    #[test]
+    fn test_whitespace_tokenizer_keeps_every_word() {
+        assert_eq!(WhitespaceTokenizer.tokenize("Wie erstelle ich Tickets"), vec!["wie", "erstelle", "ich", "tickets"]);
+    }
+}
@@ -0,0 +1,58 @@
+use crate::models::{ApiEndpoint, ApiParameter, HttpMethod, ParamType};
+
+/// Hand-maintained endpoints used when a scrape of `section` comes back too thin to be useful.
+/// Every entry is marked `fallback` so it can be told apart from scraped data. Only the ticket
+/// section is curated; other sections have no fallback.
+pub fn fallback_endpoints(section: &str) -> Vec<ApiEndpoint> {
+    if section != "ticket" {
+        return Vec::new();
+    }
+
+    // The same ticket fields: all required on create, all optional on update
+    let ticket_fields = |required: bool| vec![
+        param("subject", ParamType::String, "Subject of the ticket", required),
+        param("description", ParamType::String, "HTML content of the ticket", required),
+        param("priority", ParamType::Number, "Priority: 1 Low, 2 Medium, 3 High, 4 Urgent", required),
+        param("status", ParamType::Number, "Status: 2 Open, 3 Pending, 4 Resolved, 5 Closed", required),
+    ];
+    let mut create_fields = vec![param("email", ParamType::String, "Email address of the requester", true)];
+    create_fields.extend(ticket_fields(true));
+
+    vec![
+        endpoint("Create Ticket", "Create a new ticket", HttpMethod::Post, "/api/v2/tickets", create_fields,
+            r#"curl -v -u api_key:X -H "Content-Type: application/json" -d '{"email": "tom@outerspace.com", "subject": "Support Needed...", "description": "Details about the issue...", "status": 2, "priority": 1}' -X POST 'https://domain.freshservice.com/api/v2/tickets'"#),
+        endpoint("View a Ticket", "View a ticket by its ID", HttpMethod::Get, "/api/v2/tickets/{id}", vec![],
+            "curl -v -u api_key:X -X GET 'https://domain.freshservice.com/api/v2/tickets/{id}'"),
+        endpoint("List All Tickets", "List all tickets, newest first, 30 per page by default", HttpMethod::Get, "/api/v2/tickets", vec![],
+            "curl -v -u api_key:X -X GET 'https://domain.freshservice.com/api/v2/tickets?per_page=30&page=1'"),
+        endpoint("Update a Ticket", "Update the fields of an existing ticket", HttpMethod::Put, "/api/v2/tickets/{id}", ticket_fields(false),
+            r#"curl -v -u api_key:X -H "Content-Type: application/json" -d '{"priority": 2, "status": 3}' -X PUT 'https://domain.freshservice.com/api/v2/tickets/{id}'"#),
+        endpoint("Delete a Ticket", "Move a ticket to the trash", HttpMethod::Delete, "/api/v2/tickets/{id}", vec![],
+            "curl -v -u api_key:X -X DELETE 'https://domain.freshservice.com/api/v2/tickets/{id}'"),
+    ]
+}
+
+fn endpoint(name: &str, description: &str, method: HttpMethod, path: &str, parameters: Vec<ApiParameter>, curl: &str) -> ApiEndpoint {
+    ApiEndpoint {
+        name: name.to_string(),
+        description: description.to_string(),
+        method,
+        path: path.to_string(),
+        parameters,
+        curl_example: Some(curl.to_string()),
+        // Curated by hand, so fully trusted
+        confidence: 1.0,
+        fallback: true,
+        ..Default::default()
+    }
+}
+
+fn param(name: &str, param_type: ParamType, description: &str, required: bool) -> ApiParameter {
+    ApiParameter {
+        name: name.to_string(),
+        param_type,
+        description: description.to_string(),
+        required,
+        ..Default::default()
+    }
+}
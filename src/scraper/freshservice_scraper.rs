@@ -1,11 +1,60 @@
-use crate::models::{ApiEndpoint, ApiParameter, ScrapedDocumentation};
+use super::fallback::fallback_endpoints;
+use super::PathFilter;
+use crate::config::ScraperSelectors;
+use crate::models::{ApiEndpoint, ApiParameter, ChangelogEntry, HttpMethod, PaginationInfo, PaginationStyle, ParamType, ScrapedDocumentation};
 use anyhow::{Result, Context};
 use scraper::{Html, Selector, ElementRef};
 use regex::Regex;
 
+/// Pages smaller than this are treated as a JavaScript bootstrap shell rather than rendered
+/// docs. The real API reference page is several hundred kilobytes.
+const MIN_RENDERED_PAGE_BYTES: usize = 20_000;
+
+/// Outcome of [`FreshserviceScraper::scrape_sections`].
+pub struct SectionScrape {
+    pub documentation: ScrapedDocumentation,
+    pub failed: Vec<SectionFailure>,
+    attempted: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct SectionFailure {
+    pub section: String,
+    pub error: String,
+}
+
+impl SectionScrape {
+    /// True when at least one section was scraped.
+    pub fn any_succeeded(&self) -> bool {
+        self.failed.len() < self.attempted
+    }
+    
+    /// The merged documentation, warning about failed sections; an error if none succeeded.
+    pub fn into_documentation(self) -> Result<ScrapedDocumentation> {
+        if !self.any_succeeded() {
+            let errors: Vec<String> = self.failed.iter()
+                .map(|failure| format!("{}: {}", failure.section, failure.error))
+                .collect();
+            anyhow::bail!("Every documentation section failed to scrape ({})", errors.join("; "));
+        }
+        for failure in &self.failed {
+            eprintln!("Warning: skipped section '{}': {}", failure.section, failure.error);
+        }
+        Ok(self.documentation)
+    }
+}
+
+/// Wait before the first retry of a failed page fetch; doubled for each further retry.
+const RETRY_BACKOFF: std::time::Duration = std::time::Duration::from_millis(500);
+
 pub struct FreshserviceScraper {
     base_url: String,
     client: reqwest::Client,
+    path_filter: PathFilter,
+    selectors: ScraperSelectors,
+    min_real_endpoints: usize,
+    run_timeout: Option<std::time::Duration>,
+    retries: u32,
 }
 
 impl FreshserviceScraper {
@@ -17,25 +66,173 @@ impl FreshserviceScraper {
                 .timeout(std::time::Duration::from_secs(30))
                 .build()
                 .unwrap(),
+            path_filter: PathFilter::default(),
+            selectors: ScraperSelectors::default(),
+            min_real_endpoints: 0,
+            run_timeout: None,
+            retries: 2,
         }
     }
+    
+    /// Retry a section's page fetch up to `retries` times after a server error (5xx), timeout or
+    /// connection failure. Defaults to 2.
+    pub fn with_retries(mut self, retries: u32) -> Self {
+        self.retries = retries;
+        self
+    }
+    
+    /// Bound a whole [`Self::scrape_sections`] run. Sections still in progress or not started
+    /// when `timeout` elapses are reported as failed; the ones already scraped are kept.
+    pub fn with_run_timeout(mut self, timeout: std::time::Duration) -> Self {
+        self.run_timeout = Some(timeout);
+        self
+    }
+    
+    /// Top a section up from the curated fallback when fewer than `min` endpoints are scraped.
+    /// Off (0) by default.
+    pub fn with_min_real_endpoints(mut self, min: usize) -> Self {
+        self.min_real_endpoints = min;
+        self
+    }
+    
+    /// Only keep endpoints whose path passes `path_filter`.
+    pub fn with_path_filter(mut self, path_filter: PathFilter) -> Self {
+        self.path_filter = path_filter;
+        self
+    }
+
+    /// Use `selectors` instead of the built-in ones to locate sections, code and tables.
+    pub fn with_selectors(mut self, selectors: ScraperSelectors) -> Self {
+        self.selectors = selectors;
+        self
+    }
 
-    pub async fn scrape_ticket_attributes(&self) -> Result<ScrapedDocumentation> {
-        let url = "https://api.freshservice.com/v2/#ticket";
-        println!("Scraping tickets from: {}", url);
+    /// Fetches the docs host and returns the HTTP status, to tell network problems from parse problems.
+    pub async fn check_reachable(&self) -> Result<u16> {
+        let response = self.client.get(&self.base_url).send().await
+            .with_context(|| format!("Failed to reach {}", self.base_url))?;
+        Ok(response.status().as_u16())
+    }
+
+    /// Scrapes each section (e.g. `ticket`, `problem`), continuing past failures. The
+    /// endpoints of every successful section are merged; failed sections are listed so the
+    /// caller can report or retry them.
+    pub async fn scrape_sections(&self, sections: &[String]) -> SectionScrape {
+        let mut documentation = ScrapedDocumentation {
+            base_url: self.base_url.clone(),
+            scraped_at: chrono::Utc::now(),
+            ..Default::default()
+        };
+        let mut failed = Vec::new();
+        let mut seen = std::collections::HashSet::new();
+        let deadline = self.run_timeout.map(|timeout| tokio::time::Instant::now() + timeout);
         
-        let response = self.client.get(url).send().await
-            .context("Failed to fetch documentation page")?;
-        let html_content = response.text().await?;
+        for (i, section) in sections.iter().enumerate() {
+            let scraped = match deadline {
+                Some(deadline) => tokio::time::timeout_at(deadline, self.scrape_section(section)).await,
+                None => Ok(self.scrape_section(section).await),
+            };
+            let Ok(scraped) = scraped else {
+                let timeout = self.run_timeout.unwrap_or_default();
+                eprintln!(
+                    "Warning: scrape timed out after {:?}; keeping {} endpoints from the sections already scraped",
+                    timeout, documentation.endpoints.len()
+                );
+                failed.extend(sections[i..].iter().map(|section| SectionFailure {
+                    section: section.clone(),
+                    error: format!("scrape timed out after {:?}", timeout),
+                }));
+                break;
+            };
+            match scraped {
+                Ok(scraped) => {
+                    for ep in scraped.endpoints {
+                        if seen.insert(format!("{} {}", ep.method, ep.path)) {
+                            documentation.endpoints.push(ep);
+                        }
+                    }
+                    // Version and changelog are page-wide, so the first section's copy is enough
+                    if documentation.api_version.is_none() {
+                        documentation.api_version = scraped.api_version;
+                    }
+                    if documentation.changelog.is_empty() {
+                        documentation.changelog = scraped.changelog;
+                    }
+                    documentation.js_rendered_sections.extend(scraped.js_rendered_sections);
+                }
+                Err(e) => {
+                    eprintln!("Section '{}' failed: {:#}", section, e);
+                    failed.push(SectionFailure { section: section.clone(), error: format!("{:#}", e) });
+                }
+            }
+        }
+        
+        SectionScrape { documentation, failed, attempted: sections.len() }
+    }
+    
+    pub async fn scrape_section(&self, section: &str) -> Result<ScrapedDocumentation> {
+        let url = format!("{}/v2/#{}", self.base_url, section);
+        println!("Scraping {} section from: {}", section, url);
+        
+        let html_content = self.fetch_page(&url).await?;
         
         println!("Fetched HTML: {} bytes", html_content.len());
+        self.parse_page(&html_content, section, &url)
+    }
+    
+    /// The page body at `url`, retrying transient failures with exponential backoff. Other
+    /// errors fail at once; a server error that outlasts the retries is returned like any other
+    /// page, leaving it to the curated fallback.
+    async fn fetch_page(&self, url: &str) -> Result<String> {
+        let mut attempt = 0;
+        loop {
+            let outcome = match self.client.get(url).send().await {
+                Ok(response) if response.status().is_server_error() && attempt < self.retries => {
+                    format!("HTTP {}", response.status())
+                }
+                Ok(response) => match response.text().await {
+                    Ok(body) => return Ok(body),
+                    // The connection broke off mid-body, e.g. reset by the peer
+                    Err(e) if attempt < self.retries => e.to_string(),
+                    Err(e) => return Err(e).context("Failed to read documentation page"),
+                },
+                Err(e) if (e.is_timeout() || e.is_connect()) && attempt < self.retries => e.to_string(),
+                Err(e) => return Err(e).context("Failed to fetch documentation page"),
+            };
+            let delay = RETRY_BACKOFF * 2u32.pow(attempt);
+            attempt += 1;
+            tracing::warn!(url, error = %outcome, retry = attempt, retries = self.retries, ?delay, "fetching documentation page failed; retrying");
+            tokio::time::sleep(delay).await;
+        }
+    }
+    
+    /// Documentation of one section from the fetched page at `url`.
+    fn parse_page(&self, html_content: &str, section: &str, url: &str) -> Result<ScrapedDocumentation> {
+        let mut js_rendered_sections = Vec::new();
+        if Self::looks_js_rendered(html_content) {
+            tracing::warn!(
+                section,
+                bytes = html_content.len(),
+                "the page has no /api/v2/ paths and looks like a JavaScript-rendered shell, so this section is likely incomplete; \
+                 set scrape.min_real_endpoints to top it up from the curated catalog (tickets only), \
+                 or serve an earlier complete scrape with `serve --docs <file>`"
+            );
+            js_rendered_sections.push(section.to_string());
+        }
         println!("════════════════════════════════════════════════════════════════");
         
-        let document = Html::parse_document(&html_content);
-        let endpoints = self.extract_ticket_endpoints(&document).await?;
+        let document = Html::parse_document(html_content);
+        let mut endpoints = self.extract_endpoints(&document, section)?;
+        let verified_at = chrono::Utc::now();
+        for ep in endpoints.iter_mut().filter(|ep| !ep.fallback) {
+            ep.last_verified = Some(verified_at);
+        }
+        let api_version = Self::extract_api_version(&document)
+            .or_else(|| Self::version_from_url(url));
+        let changelog = Self::extract_changelog(&document);
         
         println!("════════════════════════════════════════════════════════════════");
-        println!("Total ticket endpoints: {}", endpoints.len());
+        println!("Total {} endpoints: {}", section, endpoints.len());
         
         if !endpoints.is_empty() {
             println!("\nEndpoints:");
@@ -48,23 +245,82 @@ impl FreshserviceScraper {
             base_url: self.base_url.clone(),
             endpoints,
             scraped_at: chrono::Utc::now(),
+            api_version,
+            changelog,
+            js_rendered_sections,
         })
     }
     
-    async fn extract_ticket_endpoints(&self, document: &Html) -> Result<Vec<ApiEndpoint>> {
+    /// Heuristic for a single-page-app bootstrap served instead of the docs: a tiny page, or one
+    /// that mentions no API path at all.
+    fn looks_js_rendered(html_content: &str) -> bool {
+        html_content.len() < MIN_RENDERED_PAGE_BYTES || !html_content.contains("/api/v2/")
+    }
+    
+    /// Version stated in the page title, main heading, or a version badge ("API v2", "Version 2.1").
+    fn extract_api_version(document: &Html) -> Option<String> {
+        let selector = Selector::parse("title, h1, [id*='version'], [class*='version']").ok()?;
+        let re = Regex::new(r"(?i)\b(?:api\s+)?(?:version\s*|v)(\d+(?:\.\d+)*)\b").ok()?;
+        
+        document.select(&selector)
+            .map(|e| e.text().collect::<String>())
+            .find_map(|text| re.captures(&text).and_then(|cap| cap.get(1)).map(|m| format!("v{}", m.as_str())))
+    }
+    
+    fn version_from_url(url: &str) -> Option<String> {
+        Regex::new(r"/(v\d+)(?:/|#|$)").ok()?
+            .captures(url)
+            .and_then(|cap| cap.get(1))
+            .map(|m| m.as_str().to_string())
+    }
+    
+    /// Entries listed under changelog / release-notes sections, one per list item or paragraph.
+    fn extract_changelog(document: &Html) -> Vec<ChangelogEntry> {
+        let (Ok(section_sel), Ok(item_sel)) = (
+            Selector::parse("[id*='changelog'], [id*='change_log'], [id*='release_notes'], [id*='release-notes']"),
+            Selector::parse("li, p"),
+        ) else {
+            return Vec::new();
+        };
+        let (Ok(date_re), Ok(version_re)) = (
+            Regex::new(r"\b(\d{4}-\d{2}-\d{2}|[A-Z][a-z]+ \d{1,2}, \d{4})\b"),
+            Regex::new(r"(?i)\bv(\d+(?:\.\d+)*)\b"),
+        ) else {
+            return Vec::new();
+        };
+        
+        document.select(&section_sel)
+            .flat_map(|section| section.select(&item_sel))
+            .map(|item| item.text().collect::<String>().split_whitespace().collect::<Vec<_>>().join(" "))
+            .filter(|text| !text.is_empty())
+            .map(|text| ChangelogEntry {
+                version: version_re.captures(&text).and_then(|c| c.get(1)).map(|m| format!("v{}", m.as_str())),
+                date: date_re.captures(&text).and_then(|c| c.get(1)).map(|m| m.as_str().to_string()),
+                description: text,
+            })
+            .collect()
+    }
+    
+    /// Endpoints of one docs section. `{section}` in the section and container selectors is
+    /// replaced with `section`, and curl examples must target its collection (`/tickets`).
+    fn extract_endpoints(&self, document: &Html, section: &str) -> Result<Vec<ApiEndpoint>> {
         let mut endpoints = Vec::new();
         let mut seen = std::collections::HashSet::new();
+        let overview_ids = [format!("{}s", section), format!("{}s-panel", section), format!("{}_attributes", section)];
         
-        // Strategy 1: Extract from ticket div sections
-        println!("Extracting from ticket divs...");
-        if let Ok(selector) = Selector::parse("div[id*='ticket']") {
+        // Strategy 1: Extract from per-endpoint section divs
+        println!("Extracting from {} divs...", section);
+        if let Ok(selector) = Selector::parse(&self.selectors.section.replace("{section}", section)) {
             for div in document.select(&selector) {
                 if let Some(id) = div.value().id() {
-                    if id == "tickets" || id == "tickets-panel" || id == "ticket_attributes" {
+                    if overview_ids.iter().any(|overview| overview == id) {
                         continue;
                     }
                     
-                    if let Some(ep) = self.parse_section(div).await {
+                    if let Some(ep) = self.parse_section(div) {
+                        if !self.path_filter.allows(&ep.path) {
+                            continue;
+                        }
                         let key = format!("{} {}", ep.method, ep.path);
                         if seen.insert(key) {
                             endpoints.push(ep);
@@ -74,36 +330,81 @@ impl FreshserviceScraper {
             }
         }
         
-        // Strategy 2: Extract from code blocks in ticket section
+        // Strategy 2: Extract from code blocks in the section container
         println!("Extracting from code blocks...");
-        if let Ok(selector) = Selector::parse("div#tickets") {
-            if let Some(section) = document.select(&selector).next() {
-                let code_eps = self.extract_from_code_blocks(section).await?;
-                for ep in code_eps {
-                    let key = format!("{} {}", ep.method, ep.path);
-                    if seen.insert(key) {
-                        endpoints.push(ep);
-                    }
+        if let Ok(selector) = Selector::parse(&self.selectors.container.replace("{section}", section))
+            && let Some(container) = document.select(&selector).next()
+        {
+            let code_eps = self.extract_from_code_blocks(container, &format!("/{}s", section))?;
+            for ep in code_eps {
+                if !self.path_filter.allows(&ep.path) {
+                    continue;
+                }
+                let key = format!("{} {}", ep.method, ep.path);
+                if seen.insert(key) {
+                    endpoints.push(ep);
                 }
             }
         }
         
-        if endpoints.is_empty() {
-            println!("No endpoints found, using fallback");
+        for ep in &mut endpoints {
+            ep.confidence = Self::extraction_confidence(ep);
         }
-        
+        self.merge_fallback(&mut endpoints, section);
         Ok(endpoints)
     }
     
-    async fn parse_section(&self, element: ElementRef<'_>) -> Option<ApiEndpoint> {
-        // Get description from h2
-        let description = Selector::parse("h2").ok()
+    /// Adds curated endpoints missing from a scrape that found fewer than `min_real_endpoints`;
+    /// the few real ones are kept and take precedence.
+    fn merge_fallback(&self, endpoints: &mut Vec<ApiEndpoint>, section: &str) {
+        if endpoints.len() >= self.min_real_endpoints {
+            return;
+        }
+        let fallback = fallback_endpoints(section);
+        if fallback.is_empty() {
+            println!("Only {} {} endpoints found and no curated fallback exists for this section", endpoints.len(), section);
+            return;
+        }
+        
+        let scraped: std::collections::HashSet<String> = endpoints.iter().map(|ep| format!("{} {}", ep.method, ep.path)).collect();
+        let before = endpoints.len();
+        endpoints.extend(fallback.into_iter().filter(|ep| {
+            !scraped.contains(&format!("{} {}", ep.method, ep.path)) && self.path_filter.allows(&ep.path)
+        }));
+        println!(
+            "Only {} {} endpoints found (minimum {}); merged {} curated fallback endpoints",
+            before, section, self.min_real_endpoints, endpoints.len() - before
+        );
+    }
+    
+    /// Completeness of a scraped entry: a real description, documented parameters, a curl
+    /// example, and a well-formed API path each add to the score.
+    fn extraction_confidence(ep: &ApiEndpoint) -> f32 {
+        let description = ep.description.trim();
+        let has_description = !description.is_empty() && description != "API endpoint" && description != "Ticket Operation";
+        let has_path = ep.path.starts_with("/api/") && !ep.path.contains("//");
+        
+        [
+            (has_description, 0.3),
+            (!ep.parameters.is_empty(), 0.3),
+            (ep.curl_example.is_some(), 0.2),
+            (has_path, 0.2),
+        ]
+        .iter()
+        .filter(|(present, _)| *present)
+        .map(|(_, weight)| weight)
+        .sum()
+    }
+    
+    fn parse_section(&self, element: ElementRef<'_>) -> Option<ApiEndpoint> {
+        // Get description from the section heading
+        let description = Selector::parse(&self.selectors.heading).ok()
             .and_then(|sel| element.select(&sel).next())
             .map(|h2| h2.text().collect::<String>().trim().to_string())
             .unwrap_or_else(|| "API endpoint".to_string());
         
         // Get curl example
-        let curl = Selector::parse("pre, .highlight").ok()
+        let curl = Selector::parse(&self.selectors.code_block).ok()
             .and_then(|sel| element.select(&sel).next())
             .map(|code| code.text().collect::<String>().trim().to_string())?;
         
@@ -111,67 +412,128 @@ impl FreshserviceScraper {
             return None;
         }
         
-        // Extract method
-        let method = if curl.contains("-X POST") { "POST" }
-        else if curl.contains("-X PUT") { "PUT" }
-        else if curl.contains("-X DELETE") { "DELETE" }
-        else if curl.contains("-X PATCH") { "PATCH" }
-        else { "GET" };
+        let method = Self::extract_method(&curl)?;
         
         let path = self.extract_path(&curl)?;
         
         println!("  {} {}", method, path);
         
-        Some(ApiEndpoint {
+        // The section ID often carries a second name for the endpoint ("create_ticket" vs "New Ticket")
+        let aliases = element.value().id()
+            .map(Self::title_from_id)
+            .filter(|derived| !derived.is_empty() && !derived.eq_ignore_ascii_case(&description))
+            .into_iter()
+            .collect();
+        
+        let section_text = element.text().collect::<String>();
+        
+        let mut endpoint = ApiEndpoint {
             name: description.clone(),
+            aliases,
             description,
-            method: method.to_string(),
+            method,
             path,
             parameters: self.extract_parameters(element),
+            status_codes: Self::extract_status_codes(&section_text),
+            response_schema: self.extract_response_schema(element),
+            notes: self.extract_notes(element),
             curl_example: Some(curl),
-        })
+            ..Default::default()
+        };
+        endpoint.parameterize_ids();
+        if endpoint.is_list() {
+            endpoint.pagination = Self::extract_pagination(&section_text);
+        }
+        Some(endpoint)
+    }
+    
+    /// Text of the section's note / warning callouts, whitespace-collapsed, in page order. A
+    /// callout nested in another matching one is only kept once.
+    fn extract_notes(&self, element: ElementRef<'_>) -> Vec<String> {
+        let Ok(selector) = Selector::parse(&self.selectors.note) else {
+            return Vec::new();
+        };
+        let mut notes: Vec<String> = Vec::new();
+        for callout in element.select(&selector) {
+            let text = callout.text().collect::<String>().split_whitespace().collect::<Vec<_>>().join(" ");
+            if !text.is_empty() && !notes.iter().any(|note| note.contains(&text)) {
+                notes.push(text);
+            }
+        }
+        notes
     }
     
-    async fn extract_from_code_blocks(&self, section: ElementRef<'_>) -> Result<Vec<ApiEndpoint>> {
+    /// The first code block in the section that parses as a JSON object or array, which in these
+    /// docs is the sample response.
+    fn extract_response_schema(&self, element: ElementRef<'_>) -> Option<serde_json::Value> {
+        let selector = Selector::parse(&self.selectors.code_block).ok()?;
+        element.select(&selector)
+            .map(|code| code.text().collect::<String>())
+            .filter(|text| !text.contains("curl"))
+            .find_map(|text| {
+                let trimmed = text.trim();
+                if !(trimmed.starts_with('{') || trimmed.starts_with('[')) {
+                    return None;
+                }
+                serde_json::from_str(trimmed).ok()
+            })
+    }
+    
+    fn extract_from_code_blocks(&self, section: ElementRef<'_>, collection: &str) -> Result<Vec<ApiEndpoint>> {
         let mut endpoints = Vec::new();
         let mut seen = std::collections::HashSet::new();
         
-        if let Ok(selector) = Selector::parse("pre, .highlight") {
+        if let Ok(selector) = Selector::parse(&self.selectors.code_block) {
             for code_elem in section.select(&selector) {
                 let curl = code_elem.text().collect::<String>();
                 
-                if !curl.contains("curl") || !curl.contains("/tickets") {
+                if !curl.contains("curl") || !curl.contains(collection) {
                     continue;
                 }
                 
-                let method = if curl.contains("-X POST") { "POST" }
-                else if curl.contains("-X PUT") { "PUT" }
-                else if curl.contains("-X DELETE") { "DELETE" }
-                else if curl.contains("-X PATCH") { "PATCH" }
-                else { "GET" };
+                let Some(method) = Self::extract_method(&curl) else {
+                    continue;
+                };
                 
                 if let Some(path) = self.extract_path(&curl) {
-                    if !path.contains("/tickets") {
+                    if !path.contains(collection) {
                         continue;
                     }
                     
-                    let key = format!("{} {}", method, path);
+                    let mut endpoint = ApiEndpoint {
+                        method,
+                        path,
+                        status_codes: Self::extract_status_codes(&curl),
+                        curl_example: Some(curl.trim().to_string()),
+                        ..Default::default()
+                    };
+                    endpoint.parameterize_ids();
+                    if endpoint.is_list() {
+                        endpoint.pagination = Self::extract_pagination(&curl);
+                    }
+                    
+                    let key = format!("{} {}", method, endpoint.path);
                     if !seen.insert(key.clone()) {
                         continue;
                     }
                     
                     let description = self.find_description(code_elem)
-                        .unwrap_or_else(|| self.infer_description(&path, method));
+                        .unwrap_or_else(|| self.infer_description(&endpoint.path, method));
+                    
+                    println!("     {}", key);
                     
-                    println!("     {} {}", method, path);
+                    let inferred = self.infer_description(&endpoint.path, method);
+                    let aliases = if inferred != description && inferred != "Ticket Operation" {
+                        vec![inferred]
+                    } else {
+                        vec![]
+                    };
                     
                     endpoints.push(ApiEndpoint {
                         name: key,
+                        aliases,
                         description,
-                        method: method.to_string(),
-                        path,
-                        parameters: vec![],
-                        curl_example: Some(curl.trim().to_string()),
+                        ..endpoint
                     });
                 }
             }
@@ -180,6 +542,23 @@ impl FreshserviceScraper {
         Ok(endpoints)
     }
     
+    /// The method a curl example uses (`-X`/`--request`, defaulting to GET). Unknown methods are
+    /// logged and yield `None` so the endpoint is skipped rather than stored malformed.
+    fn extract_method(curl: &str) -> Option<HttpMethod> {
+        let re = Regex::new(r#"(?:-X|--request)\s*['"]?([A-Za-z]+)"#).ok()?;
+        let Some(cap) = re.captures(curl) else {
+            return Some(HttpMethod::Get);
+        };
+        match cap[1].parse() {
+            Ok(method) => Some(method),
+            Err(e) => {
+                eprintln!("  Skipping endpoint: {}", e);
+                None
+            }
+        }
+    }
+    
+    #[allow(clippy::collapsible_if)]
     fn extract_path(&self, text: &str) -> Option<String> {
         let patterns = vec![
             r"https://[^/]+(/api/v2/[a-zA-Z0-9/_\-{}]+)",
@@ -200,6 +579,62 @@ impl FreshserviceScraper {
         None
     }
     
+    /// Pagination details from a list endpoint's docs: cursor vs page-based, the `per_page`
+    /// ceiling, and any note about the `Link` header. `None` when the text doesn't mention paging.
+    fn extract_pagination(text: &str) -> Option<PaginationInfo> {
+        let mentions = |pattern: &str| Regex::new(pattern).is_ok_and(|re| re.is_match(text));
+        let cursor = mentions(r"(?i)\bcursor\b");
+        let paged = mentions(r"(?i)\bper_page\b|[?&]page=|\bpage\s+(?:number|parameter)|\boffset\b");
+        
+        let max_per_page = [
+            r"(?i)\bper_page\b[^.\n]*?\b(?:max(?:imum)?|up to|limit(?:ed)? (?:to|of))\D{0,20}?(\d+)",
+            r"(?i)\b(?:max(?:imum)?|up to)\s+(?:of\s+)?(\d+)\s+(?:\w+\s+)?per page",
+        ]
+        .iter()
+        .filter_map(|pattern| Regex::new(pattern).ok())
+        .find_map(|re| re.captures(text).and_then(|cap| cap[1].parse().ok()));
+        
+        let link_header = text
+            .split(['.', '\n'])
+            .map(str::trim)
+            .find(|sentence| {
+                let lower = sentence.to_lowercase();
+                lower.contains("link") && lower.contains("header")
+            })
+            .map(|sentence| format!("{}.", sentence));
+        
+        if !(cursor || paged || max_per_page.is_some() || link_header.is_some()) {
+            return None;
+        }
+        Some(PaginationInfo {
+            style: if cursor { PaginationStyle::Cursor } else { PaginationStyle::Offset },
+            max_per_page,
+            link_header,
+        })
+    }
+    
+    /// Status codes mentioned in the section ("HTTP/1.1 204 No Content", "Status: 201"); empty
+    /// when the docs don't state one.
+    fn extract_status_codes(text: &str) -> Vec<u16> {
+        let patterns = [
+            r"\bHTTP/[\d.]+\s+([1-5]\d\d)\b",
+            r"(?i)\b(?:status|response)(?:\s+code)?\s*[:\-]?\s*([1-5]\d\d)\b",
+        ];
+        
+        let mut codes: Vec<u16> = patterns.iter()
+            .filter_map(|pattern| Regex::new(pattern).ok())
+            .flat_map(|re| {
+                re.captures_iter(text)
+                    .filter_map(|cap| cap.get(1)?.as_str().parse().ok())
+                    .collect::<Vec<u16>>()
+            })
+            .collect();
+        codes.sort_unstable();
+        codes.dedup();
+        codes
+    }
+    
+    #[allow(clippy::collapsible_if)]
     fn find_description(&self, code_elem: ElementRef<'_>) -> Option<String> {
         let mut current = code_elem;
         
@@ -208,22 +643,12 @@ impl FreshserviceScraper {
                 // Check for div ID
                 if let Some(id) = parent.value().id() {
                     if !id.is_empty() {
-                        return Some(id.replace('_', " ")
-                            .split_whitespace()
-                            .map(|w| {
-                                let mut c = w.chars();
-                                match c.next() {
-                                    None => String::new(),
-                                    Some(f) => f.to_uppercase().collect::<String>() + c.as_str(),
-                                }
-                            })
-                            .collect::<Vec<_>>()
-                            .join(" "));
+                        return Some(Self::title_from_id(id));
                     }
                 }
                 
-                // Check for h2
-                if let Ok(sel) = Selector::parse("h2") {
+                // Check for a heading
+                if let Ok(sel) = Selector::parse(&self.selectors.heading) {
                     if let Some(h2) = parent.select(&sel).next() {
                         let text = h2.text().collect::<String>().trim().to_string();
                         if !text.is_empty() && text.len() < 100 {
@@ -240,8 +665,22 @@ impl FreshserviceScraper {
         None
     }
     
-    fn infer_description(&self, path: &str, method: &str) -> String {
-        match (method, path) {
+    fn title_from_id(id: &str) -> String {
+        id.replace('_', " ")
+            .split_whitespace()
+            .map(|w| {
+                let mut c = w.chars();
+                match c.next() {
+                    None => String::new(),
+                    Some(f) => f.to_uppercase().collect::<String>() + c.as_str(),
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+    
+    fn infer_description(&self, path: &str, method: HttpMethod) -> String {
+        match (method.as_str(), path) {
             ("POST", p) if p.ends_with("/tickets") => "Create a Ticket",
             ("GET", p) if p.contains("/tickets/{id}") => "View a Ticket",
             ("GET", p) if p.ends_with("/tickets") => "List All Tickets",
@@ -266,53 +705,109 @@ impl FreshserviceScraper {
     
     fn extract_parameters(&self, element: ElementRef<'_>) -> Vec<ApiParameter> {
         let mut params = Vec::new();
+        let mut custom_fields = Vec::new();
         
-        if let Ok(selector) = Selector::parse("table") {
+        if let Ok(selector) = Selector::parse(&self.selectors.table) {
             for table in element.select(&selector) {
-                let text = table.text().collect::<String>().to_lowercase();
+                // Nested tables are parsed as children of the row that contains them
+                if Self::is_nested_table(table, element) {
+                    continue;
+                }
                 
-                if text.contains("parameter") || text.contains("attribute") || text.contains("field") {
-                    if let Ok(row_sel) = Selector::parse("tr") {
-                        for row in table.select(&row_sel).skip(1) {
-                            if let Some(param) = self.parse_param_row(row) {
-                                params.push(param);
-                            }
-                        }
+                if Self::is_custom_fields_table(table) {
+                    custom_fields.extend(self.parse_table_rows(table, None));
+                    continue;
+                }
+                
+                // Docs often split attributes into "Required attributes" / "Optional attributes" tables
+                let group_required = Self::table_heading(table).and_then(|heading| {
+                    if heading.contains("optional") {
+                        Some(false)
+                    } else if heading.contains("required") || heading.contains("mandatory") {
+                        Some(true)
+                    } else {
+                        None
                     }
+                });
+                
+                let text = table.text().collect::<String>().to_lowercase();
+                
+                if group_required.is_some() || text.contains("parameter") || text.contains("attribute") || text.contains("field") {
+                    params.extend(self.parse_table_rows(table, group_required));
                 }
             }
         }
         
+        if !custom_fields.is_empty() {
+            match params.iter_mut().find(|p| p.name == "custom_fields") {
+                Some(param) => {
+                    param.param_type = ParamType::Object;
+                    param.children.extend(custom_fields);
+                }
+                None => params.push(ApiParameter {
+                    name: "custom_fields".to_string(),
+                    param_type: ParamType::Object,
+                    description: "Key value pairs containing the names and values of custom fields".to_string(),
+                    children: custom_fields,
+                    ..Default::default()
+                }),
+            }
+        }
+        
         params
     }
     
-    fn parse_param_row(&self, row: ElementRef<'_>) -> Option<ApiParameter> {
-        let selector = Selector::parse("td").ok()?;
-        let cells: Vec<_> = row.select(&selector)
-            .map(|c| c.text().collect::<String>().trim().to_string())
+    fn parse_table_rows(&self, table: ElementRef<'_>, group_required: Option<bool>) -> Vec<ApiParameter> {
+        table.descendants()
+            .filter_map(ElementRef::wrap)
+            .filter(|e| e.value().name() == "tr" && Self::enclosing_table(*e).map(|t| t.id()) == Some(table.id()))
+            .skip(1)
+            .filter_map(|row| self.parse_param_row(row, group_required))
+            .collect()
+    }
+    
+    /// `group_required` is the required-ness implied by the table's heading, if any.
+    fn parse_param_row(&self, row: ElementRef<'_>, group_required: Option<bool>) -> Option<ApiParameter> {
+        let cell_elems: Vec<_> = row.children()
+            .filter_map(ElementRef::wrap)
+            .filter(|c| c.value().name() == "td")
+            .collect();
+        let cells: Vec<_> = cell_elems.iter()
+            .map(|c| Self::text_outside_tables(*c).trim().to_string())
             .collect();
         
         if cells.len() < 2 {
             return None;
         }
         
-        let name = cells[0].clone();
+        let (name, marked_required) = Self::strip_required_marker(&cells[0]);
         let desc = cells[1].clone();
         
         if name.is_empty() {
             return None;
         }
         
-        let param_type = cells.get(2)
-            .map(|s| s.to_lowercase())
-            .unwrap_or_else(|| {
-                if desc.to_lowercase().contains("integer") { "integer" }
-                else if desc.to_lowercase().contains("boolean") { "boolean" }
-                else if desc.to_lowercase().contains("array") { "array" }
-                else { "string" }
-            }.to_string());
+        let children: Vec<ApiParameter> = Selector::parse(&self.selectors.table).ok()
+            .and_then(|sel| cell_elems.iter().find_map(|c| c.select(&sel).next()))
+            .map(|nested| self.parse_table_rows(nested, None))
+            .unwrap_or_default();
         
-        let required = desc.to_lowercase().contains("required");
+        let param_type = if !children.is_empty() {
+            ParamType::Object
+        } else {
+            cells.get(2)
+                .map(|s| ParamType::parse(s))
+                .unwrap_or_else(|| {
+                    if desc.to_lowercase().contains("integer") { ParamType::Integer }
+                    else if desc.to_lowercase().contains("boolean") { ParamType::Boolean }
+                    else if desc.to_lowercase().contains("array") { ParamType::Array }
+                    else { ParamType::String }
+                })
+        };
+        
+        let required = marked_required
+            || Self::has_required_class(row)
+            || group_required.unwrap_or_else(|| desc.to_lowercase().contains("required"));
         
         Some(ApiParameter {
             name,
@@ -320,14 +815,543 @@ impl FreshserviceScraper {
             description: desc,
             required,
             default: None,
+            children,
         })
     }
     
-           
+    fn enclosing_table(element: ElementRef<'_>) -> Option<ElementRef<'_>> {
+        element.ancestors()
+            .filter_map(ElementRef::wrap)
+            .find(|a| a.value().name() == "table")
+    }
+    
+    fn is_nested_table(table: ElementRef<'_>, root: ElementRef<'_>) -> bool {
+        table.ancestors()
+            .take_while(|a| a.id() != root.id())
+            .filter_map(ElementRef::wrap)
+            .any(|a| a.value().name() == "table")
+    }
+    
+    /// Lowercased text of the heading-like element directly preceding `table`, if any.
+    fn table_heading(table: ElementRef<'_>) -> Option<String> {
+        table.prev_siblings()
+            .filter_map(ElementRef::wrap)
+            .next()
+            .filter(|e| matches!(e.value().name(), "h3" | "h4" | "h5" | "h6" | "p" | "strong"))
+            .map(|e| e.text().collect::<String>().to_lowercase())
+    }
+    
+    /// A table introduced by a "Custom Fields" heading documents the entries of `custom_fields`.
+    fn is_custom_fields_table(table: ElementRef<'_>) -> bool {
+        Self::table_heading(table)
+            .map(|heading| heading.replace('_', " ").contains("custom fields"))
+            .unwrap_or(false)
+    }
+    
+    /// Splits inline required markers (`subject*`, `email (required)`) off a parameter name.
+    fn strip_required_marker(name: &str) -> (String, bool) {
+        let lower = name.to_ascii_lowercase();
+        for marker in ["(required)", "(mandatory)"] {
+            if let Some(pos) = lower.find(marker) {
+                let stripped = format!("{}{}", &name[..pos], &name[pos + marker.len()..]);
+                return (stripped.trim().to_string(), true);
+            }
+        }
+        match name.strip_suffix('*') {
+            Some(stripped) => (stripped.trim().to_string(), true),
+            None => (name.to_string(), false),
+        }
+    }
+    
+    /// Rows or cells styled as required, e.g. `<td class="required">` or `<span class="mandatory">`.
+    fn has_required_class(row: ElementRef<'_>) -> bool {
+        row.descendants()
+            .filter_map(ElementRef::wrap)
+            .filter(|e| Self::enclosing_table(*e).map(|t| t.id()) == Self::enclosing_table(row).map(|t| t.id()))
+            .any(|e| e.value().classes().any(|c| {
+                let c = c.to_lowercase();
+                c.contains("required") || c.contains("mandatory")
+            }))
+    }
+    
+    fn text_outside_tables(element: ElementRef<'_>) -> String {
+        let mut text = String::new();
+        for child in element.children() {
+            if let Some(t) = child.value().as_text() {
+                text.push_str(t);
+            } else if let Some(child_elem) = ElementRef::wrap(child)
+                && child_elem.value().name() != "table"
+            {
+                text.push_str(&Self::text_outside_tables(child_elem));
+            }
+        }
+        text
+    }
 }
 
 impl Default for FreshserviceScraper {
     fn default() -> Self {
         Self::new()
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn section<'a>(document: &'a Html, id: &str) -> ElementRef<'a> {
+        let selector = Selector::parse(&format!("div#{}", id)).unwrap();
+        document.select(&selector).next().unwrap()
+    }
+    
+    #[test]
+    fn test_nested_custom_fields_table() {
+        let html = Html::parse_fragment(r#"
+            <div id="create_ticket">
+              <table>
+                <tr><th>Attribute</th><th>Description</th><th>Type</th></tr>
+                <tr><td>subject</td><td>Subject of the ticket</td><td>string</td></tr>
+                <tr><td>custom_fields</td><td>Custom field values
+                  <table>
+                    <tr><th>Field</th><th>Description</th><th>Type</th></tr>
+                    <tr><td>cf_region</td><td>Region of the requester</td><td>string</td></tr>
+                    <tr><td>cf_score</td><td>Priority score</td><td>number</td></tr>
+                  </table>
+                </td><td>hash</td></tr>
+              </table>
+            </div>"#);
+        let scraper = FreshserviceScraper::new();
+        let params = scraper.extract_parameters(section(&html, "create_ticket"));
+        
+        assert_eq!(params.len(), 2);
+        assert_eq!(params[1].name, "custom_fields");
+        assert_eq!(params[1].param_type, ParamType::Object);
+        assert_eq!(params[1].description, "Custom field values");
+        let child_names: Vec<_> = params[1].children.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(child_names, vec!["cf_region", "cf_score"]);
+        assert_eq!(params[1].children[1].param_type, ParamType::Number);
+        assert!(params[0].children.is_empty());
+    }
+    
+    #[test]
+    fn test_custom_fields_sub_section_table() {
+        let html = Html::parse_fragment(r#"
+            <div id="create_ticket">
+              <table>
+                <tr><th>Attribute</th><th>Description</th><th>Type</th></tr>
+                <tr><td>subject</td><td>Subject of the ticket</td><td>string</td></tr>
+              </table>
+              <h4>Custom Fields</h4>
+              <table>
+                <tr><th>Field</th><th>Description</th><th>Type</th></tr>
+                <tr><td>cf_region</td><td>Region of the requester</td><td>string</td></tr>
+              </table>
+            </div>"#);
+        let scraper = FreshserviceScraper::new();
+        let params = scraper.extract_parameters(section(&html, "create_ticket"));
+        
+        assert_eq!(params.len(), 2);
+        assert_eq!(params[1].name, "custom_fields");
+        assert_eq!(params[1].param_type, ParamType::Object);
+        assert_eq!(params[1].children.len(), 1);
+        assert_eq!(params[1].children[0].name, "cf_region");
+    }
+    
+    #[test]
+    fn test_required_and_optional_attribute_tables() {
+        let html = Html::parse_fragment(r#"
+            <div id="create_ticket">
+              <h4>Required attributes</h4>
+              <table>
+                <tr><th>Name</th><th>Description</th><th>Type</th></tr>
+                <tr><td>email</td><td>Email address of the requester</td><td>string</td></tr>
+                <tr><td>subject</td><td>Subject of the ticket</td><td>string</td></tr>
+              </table>
+              <h4>Optional attributes</h4>
+              <table>
+                <tr><th>Name</th><th>Description</th><th>Type</th></tr>
+                <tr><td>cc_emails</td><td>Email addresses added in the cc field</td><td>array</td></tr>
+                <tr><td>priority*</td><td>Priority of the ticket</td><td>number</td></tr>
+                <tr><td><span class="mandatory">status</span></td><td>Status of the ticket</td><td>number</td></tr>
+              </table>
+            </div>"#);
+        let scraper = FreshserviceScraper::new();
+        let params = scraper.extract_parameters(section(&html, "create_ticket"));
+        let required: Vec<_> = params.iter().map(|p| (p.name.as_str(), p.required)).collect();
+        
+        assert_eq!(required, vec![
+            ("email", true),
+            ("subject", true),
+            ("cc_emails", false),
+            ("priority", true),
+            ("status", true),
+        ]);
+    }
+    
+    #[test]
+    fn test_extract_method() {
+        assert_eq!(FreshserviceScraper::extract_method("curl -X post 'https://x/api/v2/tickets'"), Some(HttpMethod::Post));
+        assert_eq!(FreshserviceScraper::extract_method("curl --request Delete https://x"), Some(HttpMethod::Delete));
+        assert_eq!(FreshserviceScraper::extract_method("curl -u key:X https://x/api/v2/tickets"), Some(HttpMethod::Get));
+        assert_eq!(FreshserviceScraper::extract_method("curl -X POSTs https://x"), None);
+    }
+    
+    #[test]
+    fn test_extract_status_codes() {
+        assert_eq!(
+            FreshserviceScraper::extract_status_codes("HTTP/1.1 204 No Content"),
+            vec![204]
+        );
+        assert_eq!(
+            FreshserviceScraper::extract_status_codes("Response: 200 OK. Status code 404 if missing"),
+            vec![200, 404]
+        );
+        assert!(FreshserviceScraper::extract_status_codes("curl -X POST ...").is_empty());
+    }
+    
+    #[test]
+    fn test_extract_version_and_changelog() {
+        let html = Html::parse_document(r#"
+            <html><head><title>Freshservice API Reference</title></head><body>
+              <h1>Freshservice REST API v2.1</h1>
+              <div id="changelog">
+                <ul>
+                  <li>2024-03-01 - v2.1 Added workspace_id to tickets</li>
+                  <li>Deprecated the v1 API</li>
+                </ul>
+              </div>
+            </body></html>"#);
+        
+        let changelog = FreshserviceScraper::extract_changelog(&html);
+        
+        assert_eq!(FreshserviceScraper::extract_api_version(&html), Some("v2.1".to_string()));
+        assert_eq!(changelog.len(), 2);
+        assert_eq!(changelog[0].date.as_deref(), Some("2024-03-01"));
+        assert_eq!(changelog[0].version.as_deref(), Some("v2.1"));
+        assert_eq!(changelog[1].date, None);
+        assert_eq!(
+            FreshserviceScraper::version_from_url("https://api.freshservice.com/v2/#ticket"),
+            Some("v2".to_string())
+        );
+    }
+    
+    #[test]
+    fn test_path_filter_applies_during_extraction() {
+        let html = Html::parse_document(r#"
+            <div id="create_ticket">
+              <h2>Create a Ticket</h2>
+              <pre>curl -X POST 'https://domain.freshservice.com/api/v2/tickets'</pre>
+            </div>
+            <div id="create_ticket_task">
+              <h2>Create a Ticket Task</h2>
+              <pre>curl -X POST 'https://domain.freshservice.com/api/v2/tickets/1/tasks'</pre>
+            </div>"#);
+        let scraper = FreshserviceScraper::new()
+            .with_path_filter(PathFilter::new(&[], &["*/tasks*".to_string()]).unwrap());
+        
+        let endpoints = scraper.extract_endpoints(&html, "ticket").unwrap();
+        let paths: Vec<_> = endpoints.iter().map(|e| e.path.as_str()).collect();
+        
+        assert_eq!(paths, vec!["/api/v2/tickets"]);
+    }
+    
+    #[test]
+    fn test_custom_selectors_follow_changed_markup() {
+        let html = Html::parse_document(r#"
+            <article class="endpoint" id="create_ticket">
+              <h3>Create a Ticket</h3>
+              <div class="code">curl -X POST 'https://domain.freshservice.com/api/v2/tickets'</div>
+            </article>"#);
+        
+        assert!(FreshserviceScraper::new().extract_endpoints(&html, "ticket").unwrap().is_empty());
+        
+        let scraper = FreshserviceScraper::new().with_selectors(ScraperSelectors {
+            section: "article.endpoint".to_string(),
+            code_block: "div.code".to_string(),
+            heading: "h3".to_string(),
+            ..Default::default()
+        });
+        let endpoints = scraper.extract_endpoints(&html, "ticket").unwrap();
+        
+        assert_eq!(endpoints.len(), 1);
+        assert_eq!(endpoints[0].name, "Create a Ticket");
+        assert_eq!(endpoints[0].method, HttpMethod::Post);
+    }
+    
+    #[test]
+    fn test_section_name_drives_selectors() {
+        let html = Html::parse_document(r#"
+            <div id="create_ticket">
+              <h2>Create a Ticket</h2>
+              <pre>curl -X POST 'https://domain.freshservice.com/api/v2/tickets'</pre>
+            </div>
+            <div id="create_problem">
+              <h2>Create a Problem</h2>
+              <pre>curl -X POST 'https://domain.freshservice.com/api/v2/problems'</pre>
+            </div>"#);
+        let scraper = FreshserviceScraper::new();
+        
+        let problems = scraper.extract_endpoints(&html, "problem").unwrap();
+        
+        assert_eq!(problems.len(), 1);
+        assert_eq!(problems[0].path, "/api/v2/problems");
+    }
+    
+    #[tokio::test]
+    async fn test_run_timeout_keeps_sections_scraped_before_deadline() {
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+        
+        let page = r#"<div id="create_ticket"><h2>Create a Ticket</h2><pre>curl -X POST 'https://domain.freshservice.com/api/v2/tickets'</pre></div>"#;
+        let server = MockServer::start().await;
+        Mock::given(wiremock::matchers::any())
+            .respond_with(ResponseTemplate::new(200).set_body_string(page))
+            .up_to_n_times(1)
+            .mount(&server)
+            .await;
+        Mock::given(wiremock::matchers::any())
+            .respond_with(ResponseTemplate::new(200).set_body_string(page).set_delay(std::time::Duration::from_secs(10)))
+            .mount(&server)
+            .await;
+        let scraper = FreshserviceScraper {
+            base_url: server.uri(),
+            ..FreshserviceScraper::new().with_run_timeout(std::time::Duration::from_millis(500))
+        };
+        
+        let started = std::time::Instant::now();
+        let scrape = scraper.scrape_sections(&["ticket".to_string(), "problem".to_string(), "change".to_string()]).await;
+        
+        assert!(started.elapsed() < std::time::Duration::from_secs(5));
+        assert_eq!(scrape.documentation.endpoints.len(), 1);
+        let failed: Vec<&str> = scrape.failed.iter().map(|failure| failure.section.as_str()).collect();
+        assert_eq!(failed, vec!["problem", "change"]);
+        assert!(scrape.failed[0].error.contains("timed out"));
+        assert!(scrape.any_succeeded());
+    }
+    
+    #[tokio::test]
+    async fn test_flaky_page_succeeds_on_retry() {
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+        
+        let page = r#"<div id="create_ticket"><h2>Create a Ticket</h2><pre>curl -X POST 'https://domain.freshservice.com/api/v2/tickets'</pre></div>"#;
+        let server = MockServer::start().await;
+        Mock::given(wiremock::matchers::any())
+            .respond_with(ResponseTemplate::new(503))
+            .up_to_n_times(1)
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(wiremock::matchers::any())
+            .respond_with(ResponseTemplate::new(200).set_body_string(page))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let scraper = FreshserviceScraper { base_url: server.uri(), ..FreshserviceScraper::new() };
+        
+        let documentation = scraper.scrape_section("ticket").await.unwrap();
+        
+        assert_eq!(documentation.endpoints.len(), 1);
+        assert_eq!(documentation.endpoints[0].path, "/api/v2/tickets");
+        
+        // Client errors are not retried
+        server.reset().await;
+        Mock::given(wiremock::matchers::any())
+            .respond_with(ResponseTemplate::new(404))
+            .expect(1)
+            .mount(&server)
+            .await;
+        assert!(scraper.scrape_section("ticket").await.unwrap().endpoints.is_empty());
+    }
+    
+    #[tokio::test]
+    async fn test_body_cut_off_mid_read_is_retried() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+        
+        let page = r#"<div id="create_ticket"><h2>Create a Ticket</h2><pre>curl -X POST 'https://domain.freshservice.com/api/v2/tickets'</pre></div>"#;
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            // First connection promises a longer body than it sends, then closes
+            let responses = [
+                format!("HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}", page.len() * 2, page),
+                format!("HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}", page.len(), page),
+            ];
+            for response in responses {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let mut request = [0u8; 4096];
+                let _ = socket.read(&mut request).await;
+                socket.write_all(response.as_bytes()).await.unwrap();
+            }
+        });
+        let scraper = FreshserviceScraper { base_url: format!("http://{}", address), ..FreshserviceScraper::new() };
+        
+        let documentation = scraper.scrape_section("ticket").await.unwrap();
+        
+        assert_eq!(documentation.endpoints.len(), 1);
+    }
+    
+    #[test]
+    fn test_partial_section_failure_keeps_successful_sections() {
+        let scrape = |failed: Vec<&str>, attempted| SectionScrape {
+            documentation: ScrapedDocumentation {
+                endpoints: vec![ApiEndpoint { name: "Create a Ticket".to_string(), ..Default::default() }],
+                ..Default::default()
+            },
+            failed: failed.into_iter()
+                .map(|section| SectionFailure { section: section.to_string(), error: "timed out".to_string() })
+                .collect(),
+            attempted,
+        };
+        
+        let partial = scrape(vec!["problem"], 2);
+        assert!(partial.any_succeeded());
+        assert_eq!(partial.into_documentation().unwrap().endpoints.len(), 1);
+        
+        let err = scrape(vec!["ticket", "problem"], 2).into_documentation().unwrap_err();
+        assert!(err.to_string().contains("ticket: timed out; problem: timed out"));
+    }
+    
+    #[test]
+    fn test_response_schema_from_sample_response() {
+        let html = Html::parse_fragment(r#"
+            <div id="view_ticket">
+              <h2>View a Ticket</h2>
+              <pre>curl -u api_key:X -X GET 'https://domain.freshservice.com/api/v2/tickets/1'</pre>
+              <pre>{"ticket": {"id": 1, "subject": "Printer on fire"}}</pre>
+            </div>"#);
+        let endpoint = FreshserviceScraper::new().parse_section(section(&html, "view_ticket")).unwrap();
+        
+        assert_eq!(endpoint.response_schema, Some(serde_json::json!({"ticket": {"id": 1, "subject": "Printer on fire"}})));
+    }
+    
+    #[test]
+    fn test_pagination_from_list_section() {
+        let html = Html::parse_fragment(r#"
+            <div id="list_all_tickets">
+              <h2>View all Tickets</h2>
+              <p>Results are paginated. Use the per_page parameter to set the page size, up to a maximum of 100.
+              The response includes a Link header pointing at the next page when more results exist.</p>
+              <pre>curl -u api_key:X -X GET 'https://domain.freshservice.com/api/v2/tickets?page=2'</pre>
+            </div>
+            <div id="view_ticket">
+              <p>Use the per_page parameter, up to 100.</p>
+              <pre>curl -u api_key:X -X GET 'https://domain.freshservice.com/api/v2/tickets/1'</pre>
+            </div>"#);
+        let scraper = FreshserviceScraper::new();
+        
+        let list = scraper.parse_section(section(&html, "list_all_tickets")).unwrap();
+        assert_eq!(list.pagination, Some(PaginationInfo {
+            style: PaginationStyle::Offset,
+            max_per_page: Some(100),
+            link_header: Some("The response includes a Link header pointing at the next page when more results exist.".to_string()),
+        }));
+        
+        let single = scraper.parse_section(section(&html, "view_ticket")).unwrap();
+        assert_eq!(single.pagination, None);
+    }
+    
+    #[test]
+    fn test_entities_are_decoded_exactly_once() {
+        let html = Html::parse_fragment(r#"
+            <div id="create_ticket">
+              <h2>Create a Ticket &amp; Notify</h2>
+              <pre>curl -H &quot;Content-Type: application/json&quot; -d '{"description": "&amp;lt;p&amp;gt;VPN down&amp;lt;/p&amp;gt;"}' -X POST 'https://domain.freshservice.com/api/v2/tickets'</pre>
+              <table>
+                <tr><th>Attribute</th><th>Description</th><th>Type</th></tr>
+                <tr><td>subject</td><td>Subject &amp; summary, e.g. &#39;VPN down&#39; &lt;required&gt;</td><td>string</td></tr>
+              </table>
+            </div>"#);
+        
+        let endpoint = FreshserviceScraper::new().parse_section(section(&html, "create_ticket")).unwrap();
+        
+        assert_eq!(endpoint.name, "Create a Ticket & Notify");
+        assert_eq!(endpoint.parameters[0].description, "Subject & summary, e.g. 'VPN down' <required>");
+        let curl = endpoint.curl_example.unwrap();
+        assert!(curl.starts_with(r#"curl -H "Content-Type: application/json""#));
+        // Markup the docs show escaped on purpose stays escaped
+        assert!(curl.contains("&lt;p&gt;VPN down&lt;/p&gt;"));
+    }
+    
+    #[test]
+    fn test_thin_scrape_merges_fallback() {
+        let html = Html::parse_document(r#"
+            <div id="create_ticket">
+              <h2>Create a Ticket (live)</h2>
+              <pre>curl -X POST 'https://domain.freshservice.com/api/v2/tickets'</pre>
+            </div>"#);
+        let scraper = FreshserviceScraper::new().with_min_real_endpoints(3);
+        
+        let endpoints = scraper.extract_endpoints(&html, "ticket").unwrap();
+        
+        assert_eq!(endpoints.len(), 5);
+        assert_eq!(endpoints[0].name, "Create a Ticket (live)");
+        assert!(!endpoints[0].fallback);
+        assert!(endpoints[1..].iter().all(|ep| ep.fallback && ep.confidence == 1.0));
+        assert_eq!(endpoints.iter().filter(|ep| ep.method == HttpMethod::Post).count(), 1);
+        
+        // Enough real endpoints, or no curated data for the section: nothing is merged
+        assert_eq!(FreshserviceScraper::new().with_min_real_endpoints(1).extract_endpoints(&html, "ticket").unwrap().len(), 1);
+        assert!(scraper.extract_endpoints(&html, "problem").unwrap().is_empty());
+    }
+    
+    #[test]
+    fn test_js_shell_page_is_flagged() {
+        let shell = r#"<!DOCTYPE html><html><head><script src="/assets/app.js"></script></head>
+            <body><div id="root"></div><noscript>Please enable JavaScript.</noscript></body></html>"#;
+        let scraper = FreshserviceScraper::new();
+        
+        let documentation = scraper.parse_page(shell, "ticket", "https://api.freshservice.com/v2/#ticket").unwrap();
+        
+        assert!(documentation.endpoints.is_empty());
+        assert_eq!(documentation.js_rendered_sections, vec!["ticket"]);
+        
+        let rendered = format!(
+            "<div id=\"create_ticket\"><h2>Create a Ticket</h2><pre>curl -X POST 'https://domain.freshservice.com/api/v2/tickets'</pre></div>{}",
+            "<p>Freshservice API reference</p>".repeat(1000)
+        );
+        let documentation = scraper.parse_page(&rendered, "ticket", "https://api.freshservice.com/v2/#ticket").unwrap();
+        assert_eq!(documentation.endpoints.len(), 1);
+        assert!(documentation.js_rendered_sections.is_empty());
+    }
+    
+    #[test]
+    fn test_warning_callouts_become_notes() {
+        let html = Html::parse_fragment(r#"
+            <div id="delete_ticket">
+              <h2>Delete a Ticket</h2>
+              <div class="warning"><p><strong>Warning:</strong> Deleting a ticket is
+                 irreversible.</p></div>
+              <blockquote><div class="note">Only admins can delete tickets &amp; their tasks.</div></blockquote>
+              <pre>curl -v -u api_key:X -X DELETE 'https://domain.freshservice.com/api/v2/tickets/1'</pre>
+            </div>"#);
+        let scraper = FreshserviceScraper::new();
+        
+        let endpoint = scraper.parse_section(section(&html, "delete_ticket")).unwrap();
+        
+        assert_eq!(endpoint.notes, vec![
+            "Warning: Deleting a ticket is irreversible.",
+            "Only admins can delete tickets & their tasks.",
+        ]);
+    }
+    
+    #[test]
+    fn test_extraction_confidence_reflects_completeness() {
+        let html = Html::parse_document(r#"
+            <div id="create_ticket">
+              <h2>Create a Ticket</h2>
+              <pre>curl -X POST 'https://domain.freshservice.com/api/v2/tickets'</pre>
+              <table>
+                <tr><th>Attribute</th><th>Description</th><th>Type</th></tr>
+                <tr><td>subject</td><td>Subject of the ticket</td><td>string</td></tr>
+              </table>
+            </div>
+            <div id="delete_ticket">
+              <pre>curl -X DELETE 'https://domain.freshservice.com/api/v2/tickets/1'</pre>
+            </div>"#);
+        
+        let endpoints = FreshserviceScraper::new().extract_endpoints(&html, "ticket").unwrap();
+        
+        assert_eq!(endpoints[0].confidence, 1.0);
+        // No heading (placeholder description) and no parameter table
+        assert!((endpoints[1].confidence - 0.4).abs() < 1e-6);
+    }
+}
@@ -1,3 +1,8 @@
+mod fallback;
 mod freshservice_scraper;
+mod path_filter;
+mod remote;
 
-pub use freshservice_scraper::FreshserviceScraper;
\ No newline at end of file
+pub use freshservice_scraper::FreshserviceScraper;
+pub use path_filter::PathFilter;
+pub use remote::{is_url, load_remote};
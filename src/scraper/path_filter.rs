@@ -0,0 +1,55 @@
+use anyhow::{Context, Result};
+use glob::Pattern;
+
+/// Glob-based allow/deny list applied to endpoint paths during extraction.
+///
+/// An empty include list allows every path; exclude patterns always win.
+#[derive(Debug, Clone, Default)]
+pub struct PathFilter {
+    include: Vec<Pattern>,
+    exclude: Vec<Pattern>,
+}
+
+impl PathFilter {
+    pub fn new(include: &[String], exclude: &[String]) -> Result<Self> {
+        Ok(Self {
+            include: Self::compile(include)?,
+            exclude: Self::compile(exclude)?,
+        })
+    }
+    
+    fn compile(patterns: &[String]) -> Result<Vec<Pattern>> {
+        patterns.iter()
+            .map(|p| Pattern::new(p).with_context(|| format!("Invalid path pattern '{}'", p)))
+            .collect()
+    }
+    
+    pub fn allows(&self, path: &str) -> bool {
+        (self.include.is_empty() || self.include.iter().any(|p| p.matches(path)))
+            && !self.exclude.iter().any(|p| p.matches(path))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_include_and_exclude_patterns() {
+        let filter = PathFilter::new(
+            &["/api/v2/tickets*".to_string()],
+            &["*/tasks*".to_string()],
+        ).unwrap();
+        
+        assert!(filter.allows("/api/v2/tickets"));
+        assert!(filter.allows("/api/v2/tickets/{id}/notes"));
+        assert!(!filter.allows("/api/v2/tickets/{id}/tasks"));
+        assert!(!filter.allows("/api/v2/agents"));
+        assert!(PathFilter::default().allows("/api/v2/agents"));
+    }
+    
+    #[test]
+    fn test_invalid_pattern_is_rejected() {
+        assert!(PathFilter::new(&["[".to_string()], &[]).is_err());
+    }
+}
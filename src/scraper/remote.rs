@@ -0,0 +1,191 @@
+use super::FreshserviceScraper;
+use crate::config::Config;
+use crate::models::ScrapedDocumentation;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::LazyLock;
+use std::time::Duration;
+
+/// Shared by every [`RemoteDocs`], so reloads and `--watch` refreshes reuse pooled connections
+/// instead of building a new client each time.
+static CLIENT: LazyLock<reqwest::Client> = LazyLock::new(|| {
+    reqwest::Client::builder()
+        .timeout(Duration::from_secs(30))
+        .build()
+        .unwrap()
+});
+
+/// Whether a `--docs` argument names a hosted catalog rather than a local file.
+pub fn is_url(location: &str) -> bool {
+    location.starts_with("https://") || location.starts_with("http://")
+}
+
+/// Loads the catalog at `url` through the `[remote_docs]` cache. When the fetch fails and
+/// `remote_docs.fallback_to_live` is set, the docs site is scraped instead.
+pub async fn load_remote(url: &str, config: &Config) -> Result<ScrapedDocumentation> {
+    let remote = RemoteDocs::new(url)
+        .with_cache(&config.remote_docs.cache, Duration::from_secs(config.remote_docs.ttl_secs));
+    match remote.load().await {
+        Ok(documentation) => Ok(documentation),
+        Err(e) if config.remote_docs.fallback_to_live => {
+            eprintln!("Warning: {:#}; scraping live documentation instead", e);
+            FreshserviceScraper::new()
+                .with_selectors(config.scrape.selectors.clone())
+                .with_min_real_endpoints(config.scrape.min_real_endpoints)
+                .scrape_sections(&config.scrape.sections)
+                .await
+                .into_documentation()
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// A pre-scraped documentation file served over HTTP, so a fleet of servers can share one
+/// centrally hosted catalog instead of each scraping the docs site.
+pub struct RemoteDocs {
+    url: String,
+    client: reqwest::Client,
+    cache: Option<(PathBuf, Duration)>,
+}
+
+/// On-disk copy of a fetched catalog. The URL is kept so a cache written for another source is
+/// never served.
+#[derive(Serialize, Deserialize)]
+struct CachedDocs {
+    url: String,
+    fetched_at: DateTime<Utc>,
+    documentation: ScrapedDocumentation,
+}
+
+impl RemoteDocs {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            client: CLIENT.clone(),
+            cache: None,
+        }
+    }
+
+    /// Keep a copy at `path` and serve it instead of refetching while it is younger than `ttl`.
+    pub fn with_cache(mut self, path: impl Into<PathBuf>, ttl: Duration) -> Self {
+        self.cache = Some((path.into(), ttl));
+        self
+    }
+
+    pub async fn load(&self) -> Result<ScrapedDocumentation> {
+        if let Some(documentation) = self.fresh_cache() {
+            println!("Using cached documentation for {}", self.url);
+            return Ok(documentation);
+        }
+
+        let documentation = self.fetch().await?;
+        if let Err(e) = self.write_cache(&documentation) {
+            eprintln!("Warning: could not cache documentation from {}: {}", self.url, e);
+        }
+        Ok(documentation)
+    }
+
+    async fn fetch(&self) -> Result<ScrapedDocumentation> {
+        println!("Fetching documentation from {}...", self.url);
+        let response = self.client.get(&self.url)
+            .send()
+            .await
+            .with_context(|| format!("Failed to fetch documentation from {}", self.url))?;
+        let status = response.status();
+        if !status.is_success() {
+            anyhow::bail!("Failed to fetch documentation from {}: HTTP {}", self.url, status);
+        }
+        response.json()
+            .await
+            .with_context(|| format!("{} did not return scraped documentation JSON", self.url))
+    }
+
+    fn fresh_cache(&self) -> Option<ScrapedDocumentation> {
+        let (path, ttl) = self.cache.as_ref()?;
+        let cached: CachedDocs = serde_json::from_str(&std::fs::read_to_string(path).ok()?).ok()?;
+        let age = (Utc::now() - cached.fetched_at).to_std().ok()?;
+        (cached.url == self.url && age < *ttl).then_some(cached.documentation)
+    }
+
+    fn write_cache(&self, documentation: &ScrapedDocumentation) -> Result<()> {
+        let Some((path, _)) = &self.cache else {
+            return Ok(());
+        };
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let cached = CachedDocs {
+            url: self.url.clone(),
+            fetched_at: Utc::now(),
+            documentation: documentation.clone(),
+        };
+        std::fs::write(path, serde_json::to_string(&cached)?)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{ApiEndpoint, HttpMethod};
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn documentation() -> ScrapedDocumentation {
+        ScrapedDocumentation {
+            base_url: "https://api.freshservice.com".to_string(),
+            endpoints: vec![ApiEndpoint {
+                name: "Create Ticket".to_string(),
+                method: HttpMethod::Post,
+                path: "/api/v2/tickets".to_string(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fetches_once_then_serves_cache_within_ttl() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/documentation.json"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(documentation()))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let cache = std::env::temp_dir().join(format!("freshservice-rag-remote-{}.json", std::process::id()));
+        let _ = std::fs::remove_file(&cache);
+
+        let remote = RemoteDocs::new(format!("{}/documentation.json", server.uri()))
+            .with_cache(&cache, Duration::from_secs(60));
+        for _ in 0..2 {
+            let loaded = remote.load().await.unwrap();
+            assert_eq!(loaded.endpoints.len(), 1);
+            assert_eq!(loaded.endpoints[0].name, "Create Ticket");
+        }
+
+        // A cache written for another URL is ignored
+        let other = RemoteDocs::new(format!("{}/other.json", server.uri())).with_cache(&cache, Duration::from_secs(60));
+        assert!(other.fresh_cache().is_none());
+
+        std::fs::remove_file(&cache).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_http_error_is_reported_with_url_and_status() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&server)
+            .await;
+
+        let url = format!("{}/documentation.json", server.uri());
+        let error = RemoteDocs::new(url.clone()).load().await.unwrap_err().to_string();
+        assert!(error.contains(&url), "{}", error);
+        assert!(error.contains("404"), "{}", error);
+        assert!(is_url(&url));
+        assert!(!is_url("data/scraped/documentation.json"));
+    }
+}
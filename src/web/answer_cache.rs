@@ -0,0 +1,103 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+const CACHE_FILE: &str = "answers.json";
+
+/// LLM answers persisted to disk so identical questions are answered without the LLM, even
+/// after a restart. Entries belong to one documentation snapshot: when the documentation hash
+/// changes (a `/reload` or re-scrape with different docs), every cached answer is dropped.
+/// Deterministic and regular answers are cached separately.
+pub struct AnswerCache {
+    path: PathBuf,
+    file: Mutex<CacheFile>,
+    /// Serializes disk writes so a slower, older snapshot never overwrites a newer one.
+    write: tokio::sync::Mutex<()>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CacheFile {
+    documentation_hash: String,
+    answers: HashMap<String, String>,
+}
+
+impl AnswerCache {
+    /// Opens the cache in `dir`, creating the directory if needed. An unreadable cache file
+    /// starts the cache empty.
+    pub fn open(dir: &Path) -> Result<Self> {
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create answer cache directory {}", dir.display()))?;
+        let path = dir.join(CACHE_FILE);
+        let file = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        Ok(Self { path, file: Mutex::new(file), write: tokio::sync::Mutex::new(()) })
+    }
+
+    /// The cached answer to `query` for the documentation with `documentation_hash`.
+    pub fn get(&self, query: &str, deterministic: bool, documentation_hash: &str) -> Option<String> {
+        let file = self.file.lock().unwrap();
+        if file.documentation_hash != documentation_hash {
+            return None;
+        }
+        file.answers.get(&key(query, deterministic)).cloned()
+    }
+
+    /// Stores `answer` and writes the cache to disk, first discarding answers cached for
+    /// different documentation.
+    pub async fn insert(&self, query: &str, deterministic: bool, documentation_hash: &str, answer: &str) -> Result<()> {
+        let _write = self.write.lock().await;
+        let content = {
+            let mut file = self.file.lock().unwrap();
+            if file.documentation_hash != documentation_hash {
+                *file = CacheFile { documentation_hash: documentation_hash.to_string(), answers: HashMap::new() };
+            }
+            file.answers.insert(key(query, deterministic), answer.to_string());
+            serde_json::to_string(&*file)?
+        };
+        tokio::fs::write(&self.path, content)
+            .await
+            .with_context(|| format!("Failed to write answer cache {}", self.path.display()))
+    }
+}
+
+/// Case and spacing don't change a question: "Create  a Ticket?" and "create a ticket?" share
+/// an entry.
+fn normalize(query: &str) -> String {
+    query.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase()
+}
+
+fn key(query: &str, deterministic: bool) -> String {
+    if deterministic {
+        format!("[deterministic] {}", normalize(query))
+    } else {
+        normalize(query)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_answers_survive_restart_until_docs_change() {
+        let dir = std::env::temp_dir().join(format!("freshservice-rag-answers-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+
+        AnswerCache::open(&dir).unwrap().insert("How do I create a ticket?", false, "hash-1", "POST /api/v2/tickets").await.unwrap();
+
+        let reopened = AnswerCache::open(&dir).unwrap();
+        assert_eq!(reopened.get("  how do I  CREATE a ticket? ", false, "hash-1").as_deref(), Some("POST /api/v2/tickets"));
+        assert_eq!(reopened.get("How do I create a ticket?", true, "hash-1"), None);
+        assert_eq!(reopened.get("How do I create a ticket?", false, "hash-2"), None);
+
+        reopened.insert("delete a ticket", false, "hash-2", "DELETE /api/v2/tickets/{id}").await.unwrap();
+        assert_eq!(reopened.get("How do I create a ticket?", false, "hash-1"), None);
+        assert!(AnswerCache::open(&dir).unwrap().get("delete a ticket", false, "hash-2").is_some());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
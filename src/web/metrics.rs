@@ -0,0 +1,62 @@
+use crate::llm::TokenUsage;
+use serde::Serialize;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Process-wide counters served at `/metrics`.
+#[derive(Debug, Default)]
+pub struct Metrics {
+    queries: AtomicU64,
+    prompt_tokens: AtomicU64,
+    completion_tokens: AtomicU64,
+    total_tokens: AtomicU64,
+    llm_in_flight: AtomicU64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MetricsSnapshot {
+    pub queries: u64,
+    pub tokens: TokenUsage,
+    /// LLM calls currently running.
+    pub llm_in_flight: u64,
+}
+
+/// Counts an LLM call as in flight until dropped.
+pub struct InFlightGuard<'a> {
+    metrics: &'a Metrics,
+}
+
+impl Drop for InFlightGuard<'_> {
+    fn drop(&mut self) {
+        self.metrics.llm_in_flight.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+impl Metrics {
+    /// Counts one answered query and adds its token usage, if the LLM reported any.
+    pub fn record_query(&self, usage: Option<TokenUsage>) {
+        self.queries.fetch_add(1, Ordering::Relaxed);
+        if let Some(usage) = usage {
+            self.prompt_tokens.fetch_add(usage.prompt, Ordering::Relaxed);
+            self.completion_tokens.fetch_add(usage.completion, Ordering::Relaxed);
+            self.total_tokens.fetch_add(usage.total, Ordering::Relaxed);
+        }
+    }
+
+    /// Marks an LLM call as started; it counts as in flight until the guard is dropped.
+    pub fn llm_call(&self) -> InFlightGuard<'_> {
+        self.llm_in_flight.fetch_add(1, Ordering::Relaxed);
+        InFlightGuard { metrics: self }
+    }
+
+    pub fn snapshot(&self) -> MetricsSnapshot {
+        MetricsSnapshot {
+            queries: self.queries.load(Ordering::Relaxed),
+            tokens: TokenUsage {
+                prompt: self.prompt_tokens.load(Ordering::Relaxed),
+                completion: self.completion_tokens.load(Ordering::Relaxed),
+                total: self.total_tokens.load(Ordering::Relaxed),
+            },
+            llm_in_flight: self.llm_in_flight.load(Ordering::Relaxed),
+        }
+    }
+}
@@ -1,3 +1,6 @@
+mod answer_cache;
+mod metrics;
 mod server;
+mod timings;
 
-pub use server::run_server;
\ No newline at end of file
+pub use server::{parse_interval, run_server, ServerOptions};
@@ -1,14 +1,84 @@
-use crate::rag::RagPipeline;
-use crate::scraper::FreshserviceScraper;
-use crate::llm::GroqClient;
+use crate::config::Config;
+use crate::models::{apply_domain, ApiEndpoint, ScrapedDocumentation};
+use crate::rag::{append_feedback, grounding_score, tokenizer_for, Blocklist, EmbeddingCache, HashingEmbedder, FeedbackBoosts, FeedbackEntry, Intent, RagPipeline, RagRouter, StructuredAnswer};
+use crate::scraper::{is_url, load_remote, FreshserviceScraper};
+use super::answer_cache::AnswerCache;
+use super::metrics::Metrics;
+use super::timings::{timed, timed_async, StageTimings};
+use crate::llm::{CoalescingProvider, EchoContextClient, GenerationOptions, GroqClient, GroqError, LlmAnswer, LlmProvider, MockLlmProvider, TokenUsage};
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
 use std::sync::Arc;
-use warp::Filter;
+use std::time::Duration;
+use tokio::sync::{RwLock, Semaphore};
+use warp::http::{header, StatusCode};
+use warp::{Filter, Reply};
 
 #[derive(Debug, Deserialize)]
 struct QueryRequest {
     query: String,
+    /// How much detail `explanation` carries; defaults to `short`.
+    #[serde(default)]
+    explain: Option<ExplainLevel>,
+    #[serde(default)]
+    answer_mode: AnswerMode,
+    /// Ask the LLM for a reproducible answer (temperature 0, fixed seed).
+    #[serde(default)]
+    deterministic: bool,
+    /// Named tone for the answer, e.g. `developer`; see `Config::persona`.
+    #[serde(default)]
+    persona: Option<String>,
+    /// Return something other than a prose answer, e.g. just the curl example.
+    #[serde(default)]
+    output: Option<OutputKind>,
+    /// Return the best match as structured data in `structured`, alongside a one-line answer.
+    #[serde(default)]
+    answer_format: Option<AnswerFormat>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum AnswerFormat {
+    /// `{ method, path, required_params, example }` for the top match, without calling the LLM.
+    Structured,
+}
+
+/// Alternative shapes of the `/query` answer.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum OutputKind {
+    /// The top match's curl example alone, without calling the LLM.
+    Curl,
+}
+
+/// How `/query` produces the answer: by the LLM, or by quoting the best-matching endpoint.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum AnswerMode {
+    #[default]
+    Generative,
+    Extractive,
+}
+
+/// Query-string flags accepted by `POST /query`.
+#[derive(Debug, Default, Deserialize)]
+struct QueryFlags {
+    /// Include the LLM's token usage in the response.
+    #[serde(default)]
+    include_usage: bool,
+    /// Include per-stage timings in the response.
+    #[serde(default)]
+    include_timings: bool,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum ExplainLevel {
+    None,
+    #[default]
+    Short,
+    Full,
 }
 
 #[derive(Debug, Serialize)]
@@ -16,108 +86,1531 @@ struct QueryResponse {
     answer: String,
     sources: Vec<String>,
     confidence: f32,
-    explanation: String, 
+    confidence_level: String,
+    /// Share of the answer's phrases found in the retrieved context; low values suggest the
+    /// model went beyond the docs. Only set for answers the LLM wrote.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    grounding: Option<f32>,
+    explanation: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    structured: Option<StructuredAnswer>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    usage: Option<TokenUsage>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    timings: Option<StageTimings>,
+}
+
+#[derive(Debug, Deserialize)]
+struct FeedbackRequest {
+    query: String,
+    helpful: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct ContextQuery {
+    q: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct SearchQuery {
+    q: String,
+    #[serde(default = "default_search_limit")]
+    limit: usize,
+}
+
+fn default_search_limit() -> usize {
+    10
+}
+
+pub struct ServerOptions {
+    pub port: u16,
+    /// Skip the startup LLM request that verifies `GROQ_API_KEY`.
+    pub skip_preflight: bool,
+    /// Re-scrape the documentation on this interval, swapping in the new snapshot on success.
+    pub watch: Option<Duration>,
+    /// Gzip responses for clients that send `Accept-Encoding: gzip`.
+    pub compression: bool,
+    /// Serve every `*.json` documentation file in this directory as a separate, named set
+    /// instead of scraping the live docs.
+    pub docs_dir: Option<PathBuf>,
+    /// Serve this scraped documentation file or URL instead of scraping the live docs.
+    pub docs: Option<String>,
+    /// Persist generated answers here and reuse them for repeated questions.
+    pub answer_cache_dir: Option<PathBuf>,
+}
+
+const NO_RELEVANT_DOCS_ANSWER: &str = "I couldn't find any relevant information in the Freshservice documentation for your query. Please try asking about specific API endpoints like creating tickets, updating tickets, or ticket attributes.";
+
+/// How many example questions `/suggest` returns.
+const MAX_SUGGESTIONS: usize = 8;
+
+/// Shared state handed to every route. The documentation sits behind a lock so `/reload` can swap
+/// it; handlers clone the `Arc` out and release the lock at once, so a swap never waits on a request.
+#[derive(Clone)]
+struct AppState {
+    snapshot: Arc<RwLock<Arc<Snapshot>>>,
+    llm: Arc<dyn LlmProvider>,
+    config: Arc<Config>,
+    metrics: Arc<Metrics>,
+    /// Caps concurrent LLM calls at `llm.max_concurrent_requests`.
+    llm_permits: Arc<Semaphore>,
+    /// Set with `serve --docs-dir`: the directory the documentation sets are (re)loaded from.
+    docs_dir: Option<PathBuf>,
+    /// Set with `serve --docs`: the file or URL the documentation is (re)loaded from.
+    docs: Option<Arc<str>>,
+    /// Set with `serve --answer-cache-dir`.
+    answer_cache: Option<Arc<AnswerCache>>,
+}
+
+/// The documentation being served, swapped as one unit so a query never mixes a refreshed router
+/// with a stale pipeline.
+struct Snapshot {
+    pipeline: RagPipeline,
+    /// Set with `serve --docs-dir`: `/query` then searches each set separately and reports which
+    /// one answered, while `pipeline` holds all sets merged for the catalog routes.
+    router: Option<RagRouter>,
+}
+
+impl Snapshot {
+    fn new(pipeline: RagPipeline) -> Self {
+        Self { pipeline, router: None }
+    }
 }
 
-pub async fn run_server(port: u16) -> Result<()> {
+impl AppState {
+    /// The documentation currently being served. The lock is released before this returns.
+    async fn snapshot(&self) -> Arc<Snapshot> {
+        self.snapshot.read().await.clone()
+    }
+}
+
+pub async fn run_server(options: ServerOptions, config: Config) -> Result<()> {
+    let port = options.port;
+
     // Initialize components
-    let scraper = FreshserviceScraper::new();
-    let documentation = scraper.scrape_ticket_attributes().await?;
-    let rag_pipeline = Arc::new(RagPipeline::new(documentation));
-    
-    // Initialize Groq client
-    let groq_client = Arc::new(GroqClient::new(
-        std::env::var("GROQ_API_KEY").unwrap_or_else(|_| {
-            eprintln!("Warning: GROQ_API_KEY not set. Using placeholder key.");
-            "gsk_placeholder_key".to_string()
-        }),
-    ));
-    
-    let rag_pipeline_filter = rag_pipeline.clone();
-    let groq_client_filter = groq_client.clone();
-    
-    // Define routes
+    let snapshot = match &options.docs_dir {
+        Some(dir) => {
+            let snapshot = load_doc_sets(dir, &config)?;
+            if let Some(router) = &snapshot.router {
+                println!("Serving documentation sets: {}", router.sources().collect::<Vec<_>>().join(", "));
+            }
+            snapshot
+        }
+        None => Snapshot::new(load_pipeline(&config, options.docs.as_deref()).await?),
+    };
+
+    let llm = build_llm(&options, &config).await?;
+    let answer_cache = match &options.answer_cache_dir {
+        Some(dir) => Some(Arc::new(AnswerCache::open(dir)?)),
+        None => None,
+    };
+
+    let state = AppState {
+        snapshot: Arc::new(RwLock::new(Arc::new(snapshot))),
+        llm,
+        llm_permits: Arc::new(Semaphore::new(config.llm.max_concurrent_requests)),
+        config: Arc::new(config),
+        metrics: Arc::new(Metrics::default()),
+        docs_dir: options.docs_dir,
+        docs: options.docs.map(Arc::from),
+        answer_cache,
+    };
+
+    if let Some(interval) = options.watch {
+        println!("Watching documentation: re-scraping every {:?}", interval);
+        tokio::spawn(watch_documentation(state.clone(), interval));
+    }
+
+    println!("Server running on http://localhost:{}", port);
+    if options.compression {
+        warp::serve(gzip_when_accepted(routes(state)))
+            .run(([127, 0, 0, 1], port))
+            .await;
+    } else {
+        warp::serve(routes(state))
+            .run(([127, 0, 0, 1], port))
+            .await;
+    }
+
+    Ok(())
+}
+
+/// Picks the LLM backend: the mock when `LLM_BACKEND=mock`, Groq when a key is configured,
+/// and otherwise an offline client that echoes the retrieved context.
+async fn build_llm(options: &ServerOptions, config: &Config) -> Result<Arc<dyn LlmProvider>> {
+    if std::env::var("LLM_BACKEND").is_ok_and(|backend| backend.eq_ignore_ascii_case("mock")) {
+        println!("Using mock LLM backend");
+        return Ok(Arc::new(MockLlmProvider::new(std::env::var("LLM_MOCK_ANSWER").ok())));
+    }
+
+    match GroqClient::api_key_from_env() {
+        Some(api_key) => {
+            let groq_client = GroqClient::new(api_key)
+                .with_model(config.llm.model.clone())
+                .with_headers(config.llm.headers.clone())?;
+            if !options.skip_preflight {
+                preflight(&groq_client).await?;
+            }
+            Ok(Arc::new(CoalescingProvider::new(Arc::new(groq_client))))
+        }
+        None => {
+            eprintln!("Warning: GROQ_API_KEY not set. Answers will contain the retrieved documentation only, without LLM generation.");
+            Ok(Arc::new(EchoContextClient))
+        }
+    }
+}
+
+async fn preflight(groq_client: &GroqClient) -> Result<()> {
+    println!("Running LLM preflight check...");
+    match groq_client.preflight().await {
+        Ok(()) => println!("LLM preflight succeeded"),
+        Err(e) => {
+            if e.downcast_ref::<GroqError>().is_some_and(GroqError::is_auth) {
+                eprintln!("LLM preflight failed: {}", e);
+                eprintln!("Check GROQ_API_KEY or pass --skip-preflight to start anyway.");
+                return Err(e);
+            }
+            eprintln!("Warning: LLM preflight failed: {}", e);
+        }
+    }
+    Ok(())
+}
+
+/// Loads the documentation from `docs` (a file, or a URL fetched through the remote cache) when
+/// given, otherwise by scraping the live docs.
+async fn load_pipeline(config: &Config, docs: Option<&str>) -> Result<RagPipeline> {
+    let documentation = match docs {
+        Some(url) if is_url(url) => load_remote(url, config).await?,
+        Some(path) => ScrapedDocumentation::from_file(std::path::Path::new(path))?,
+        None => {
+            let scraper = FreshserviceScraper::new()
+                .with_selectors(config.scrape.selectors.clone())
+                .with_min_real_endpoints(config.scrape.min_real_endpoints);
+            scraper.scrape_sections(&config.scrape.sections).await.into_documentation()?
+        }
+    };
+    configure_pipeline(documentation, config)
+}
+
+/// One pipeline per documentation file in `dir`, each configured like the live one.
+fn load_router(dir: &std::path::Path, config: &Config) -> Result<RagRouter> {
+    RagRouter::from_dir(dir, |documentation| configure_pipeline(documentation, config))
+}
+
+/// The sets in `dir` routed separately, alongside all of them merged for the catalog routes.
+fn load_doc_sets(dir: &std::path::Path, config: &Config) -> Result<Snapshot> {
+    let router = load_router(dir, config)?;
+    let pipeline = configure_pipeline(router.merged_documentation(), config)?;
+    Ok(Snapshot { pipeline, router: Some(router) })
+}
+
+/// Applies the config (domain, feedback, blocklist, scoring, context limits) to `documentation`.
+fn configure_pipeline(mut documentation: ScrapedDocumentation, config: &Config) -> Result<RagPipeline> {
+    if let Some(host) = config.freshservice.host() {
+        documentation = documentation.with_domain(&host);
+    }
+    let feedback_boosts = FeedbackBoosts::load(&config.feedback).unwrap_or_else(|e| {
+        eprintln!("Warning: ignoring feedback log: {}", e);
+        FeedbackBoosts::default()
+    });
+    let blocklist = Blocklist::new(&config.compliance.blocked_endpoints)?;
+    let embeddings = config.embeddings.enabled.then(|| load_embeddings(&documentation, config));
+    let pipeline = RagPipeline::new(documentation)
+        .with_blocklist(&blocklist)
+        .with_tokenizer(tokenizer_for(config.scoring.tokenizer))
+        .with_environments(config.environments.clone())
+        .with_weights(config.scoring.clone())
+        .with_context_config(config.context.clone())
+        .with_feedback_boosts(feedback_boosts);
+    Ok(match embeddings {
+        Some(cache) => pipeline.with_embeddings(cache, Box::new(HashingEmbedder::default()), config.embeddings.weight),
+        None => pipeline,
+    })
+}
+
+/// The embedding cache brought up to date with `documentation`, re-embedding only endpoints
+/// whose content changed since it was last saved.
+fn load_embeddings(documentation: &ScrapedDocumentation, config: &Config) -> EmbeddingCache {
+    let path = &config.embeddings.cache;
+    let mut cache = EmbeddingCache::load(path).unwrap_or_else(|e| {
+        eprintln!("Warning: rebuilding embedding cache: {}", e);
+        EmbeddingCache::default()
+    });
+    let embedded = cache.refresh(&documentation.endpoints, &HashingEmbedder::default());
+    println!("Embeddings: {} endpoints re-embedded, {} reused", embedded, cache.len() - embedded);
+    if let Err(e) = cache.save(path) {
+        eprintln!("Warning: could not save embedding cache: {}", e);
+    }
+    cache
+}
+
+fn with_state(state: AppState) -> impl Filter<Extract = (AppState,), Error = std::convert::Infallible> + Clone {
+    warp::any().map(move || state.clone())
+}
+
+fn routes(state: AppState) -> impl Filter<Extract = (impl Reply,), Error = warp::Rejection> + Clone {
     let query_route = warp::path("query")
         .and(warp::post())
+        .and(warp::query::<QueryFlags>())
         .and(warp::body::json())
-        .and_then(move |request: QueryRequest| {
-            let rag_pipeline = rag_pipeline_filter.clone();
-            let groq_client = groq_client_filter.clone();
-            
-            async move {
-                // Process query using RAG pipeline
-                let matches = rag_pipeline.find_relevant_endpoints(&request.query);
-                let (context, max_score) = rag_pipeline.format_context(&matches);
-                
-                println!("Query: '{}'", request.query);
-                println!("Found {} relevant endpoints", matches.len());
-                println!("Max relevance score: {:.2}", max_score);
-                println!("Context length: {} characters", context.len());
-                
-                // Calculate dynamic confidence
-                let confidence = rag_pipeline.calculate_confidence(&request.query, &matches);
-                
-                let mut explanation = format!("Found {} relevant endpoints. ", matches.len());
-                if !matches.is_empty() {
-                    explanation.push_str(&format!("Best match: '{}' with score {:.2}. ", matches[0].0.name, matches[0].1));
-                }
-                explanation.push_str(&format!("Overall confidence: {:.2}", confidence));
-                
-                // Use Groq to generate answer from context
-                let answer = if context.trim().is_empty() {
-                    "I couldn't find any relevant information in the Freshservice documentation for your query. Please try asking about specific API endpoints like creating tickets, updating tickets, or ticket attributes.".to_string()
-                } else {
-                    match groq_client.generate_answer(&request.query, &context).await {
-                        Ok(answer) => answer,
-                        Err(e) => {
-                            eprintln!("Groq API error: {}", e);
-                            format!("I found some relevant information but encountered an error processing it. Here's what I found:\n\n{}", context)
-                        }
-                    }
-                };
-                
-                let sources: Vec<String> = vec!["Freshservice API Documentation".to_string()];
-                
-                Ok::<_, warp::Rejection>(warp::reply::json(&QueryResponse {
-                    answer,
-                    sources,
-                    confidence,
-                    explanation,
-                }))
-            }
-        });
-    
+        .and(with_state(state.clone()))
+        .and_then(handle_query);
+
     let health_route = warp::path("health")
         .map(|| warp::reply::json(&serde_json::json!({"status": "healthy"})));
 
     // Debug route to see available endpoints
     let debug_route = warp::path("debug")
         .and(warp::get())
-        .map(move || {
-            let documentation = rag_pipeline.get_documentation();
-            let endpoints_count = documentation.endpoints.len();
-            let endpoint_names: Vec<String> = documentation.endpoints
-                .iter()
-                .map(|e| e.name.clone())
-                .collect();
-            
-            warp::reply::json(&serde_json::json!({
-                "total_endpoints": endpoints_count,
-                "endpoints": endpoint_names,
-                "sample_endpoint": &documentation.endpoints.get(0)
-            }))
-        });
-    
-    let routes = query_route
+        .and(warp::header::optional::<String>("if-none-match"))
+        .and(with_state(state.clone()))
+        .then(handle_debug);
+
+    let endpoints_route = warp::path!("endpoints")
+        .and(warp::get())
+        .and(warp::header::optional::<String>("if-none-match"))
+        .and(with_state(state.clone()))
+        .then(handle_endpoints);
+
+    let validate_route = warp::path!("endpoints" / String / "validate")
+        .and(warp::post())
+        .and(warp::body::json())
+        .and(with_state(state.clone()))
+        .then(handle_validate);
+
+    let context_route = warp::path!("context")
+        .and(warp::get())
+        .and(warp::query::<ContextQuery>())
+        .and(warp::header::optional::<String>("if-none-match"))
+        .and(with_state(state.clone()))
+        .then(handle_context);
+
+    let search_route = warp::path!("search")
+        .and(warp::get())
+        .and(warp::query::<SearchQuery>())
+        .and(with_state(state.clone()))
+        .then(handle_search);
+
+    let suggest_route = warp::path!("suggest")
+        .and(warp::get())
+        .and(with_state(state.clone()))
+        .then(handle_suggest);
+
+    let status_route = warp::path!("status" / u16 / "endpoints")
+        .and(warp::get())
+        .and(with_state(state.clone()))
+        .then(handle_status_endpoints);
+
+    let version_route = warp::path!("version")
+        .and(warp::get())
+        .and(with_state(state.clone()))
+        .then(handle_version);
+
+    let feedback_route = warp::path!("feedback")
+        .and(warp::post())
+        .and(warp::body::json())
+        .and(with_state(state.clone()))
+        .then(handle_feedback);
+
+    let metrics_route = warp::path!("metrics")
+        .and(warp::get())
+        .and(with_state(state.clone()))
+        .map(|state: AppState| warp::reply::json(&state.metrics.snapshot()));
+
+    let reload_route = warp::path!("reload")
+        .and(warp::post())
+        .and(with_state(state))
+        .then(handle_reload);
+
+    query_route
         .or(health_route)
         .or(debug_route)
-        .with(warp::cors().allow_any_origin());
-    
-    println!("Server running on http://localhost:{}", port);
-    println!("Make sure to set GROQ_API_KEY environment variable");
-    warp::serve(routes)
-        .run(([127, 0, 0, 1], port))
-        .await;
-    
-    Ok(())
-}
\ No newline at end of file
+        .or(endpoints_route)
+        .or(validate_route)
+        .or(context_route)
+        .or(search_route)
+        .or(suggest_route)
+        .or(status_route)
+        .or(version_route)
+        .or(feedback_route)
+        .or(metrics_route)
+        .or(reload_route)
+        .with(warp::cors().allow_any_origin())
+}
+
+/// Gzip-encodes responses when the request advertises gzip support; other clients get `filter`'s
+/// replies unchanged. The two branches are mutually exclusive on the header, so `filter` runs
+/// once per request even when it rejects.
+fn gzip_when_accepted<F, R>(filter: F) -> impl Filter<Extract = (impl Reply,), Error = warp::Rejection> + Clone
+where
+    F: Filter<Extract = (R,), Error = warp::Rejection> + Clone + Send + Sync + 'static,
+    R: Reply + 'static,
+{
+    let accepts_gzip = warp::header::optional::<String>("accept-encoding").map(|accept: Option<String>| {
+        accept.is_some_and(|accept| accept.split(',').any(|encoding| encoding.trim().starts_with("gzip")))
+    });
+    let when = move |gzip: bool| {
+        accepts_gzip
+            .and_then(move |accepts: bool| async move { if accepts == gzip { Ok(()) } else { Err(warp::reject()) } })
+            .untuple_one()
+    };
+
+    when(true)
+        .and(filter.clone())
+        .with(warp::compression::gzip())
+        .or(when(false).and(filter))
+}
+
+async fn handle_query(flags: QueryFlags, request: QueryRequest, state: AppState) -> Result<warp::reply::Response, warp::Rejection> {
+    let persona = match request.persona.as_deref() {
+        Some(name) => match state.config.persona(name) {
+            Some(prompt) => Some(prompt.to_string()),
+            None => {
+                return Ok(warp::reply::with_status(
+                    warp::reply::json(&serde_json::json!({
+                        "error": format!("Unknown persona '{}'", name),
+                        "available": state.config.persona_names(),
+                    })),
+                    StatusCode::BAD_REQUEST,
+                ).into_response());
+            }
+        },
+        None => None,
+    };
+    let options = GenerationOptions { deterministic: request.deterministic, persona };
+
+    let snapshot = state.snapshot().await;
+    let rag_pipeline = &snapshot.pipeline;
+    let router = snapshot.router.as_ref();
+
+    // Process query using RAG pipeline, or across every documentation set
+    let ((routed, matches), retrieval_time) = timed(tracing::info_span!("retrieval", elapsed_ms = tracing::field::Empty), || {
+        let routed = router.as_ref().map(|router| router.find_relevant_endpoints(&request.query));
+        let matches: Vec<_> = match &routed {
+            Some(routed) => routed.iter().map(|routed| (routed.endpoint, routed.score)).collect(),
+            None => rag_pipeline.find_relevant_endpoints(&request.query),
+        };
+        (routed, matches)
+    });
+    let ((context, max_score), context_time) = timed(tracing::info_span!("context", elapsed_ms = tracing::field::Empty), || {
+        let (mut context, max_score) = rag_pipeline.format_context(&matches);
+        if let Some(version_context) = rag_pipeline.version_context(&request.query) {
+            context = format!("{}\n{}", version_context, context);
+        }
+        if let Some(response_context) = rag_pipeline.response_context(&request.query, &matches) {
+            context.push_str(&response_context);
+        }
+        if let Some(environment_context) = rag_pipeline.environment_context(&request.query) {
+            context = format!("{}\n{}", environment_context, context);
+        }
+        (context, max_score)
+    });
+
+    println!("Query: '{}'", request.query);
+    println!("Found {} relevant endpoints", matches.len());
+    println!("Max relevance score: {:.2}", max_score);
+    println!("Context length: {} characters", context.len());
+
+    // Calculate dynamic confidence
+    let confidence = rag_pipeline.calculate_confidence(&request.query, &matches);
+    let confidence_level = state.config.confidence.level(confidence).to_string();
+
+    let explanation = build_explanation(
+        request.explain.unwrap_or_default(),
+        rag_pipeline,
+        &request.query,
+        &matches,
+        confidence,
+    );
+
+    let structured = (request.answer_format == Some(AnswerFormat::Structured))
+        .then(|| rag_pipeline.structured_answer(&matches))
+        .flatten();
+
+    // Personas reword the answer, so only plain answers are shared through the cache, and only
+    // from a real model: echoed context or mock answers must not outlive the backend that wrote them
+    let answer_cache = state.answer_cache.as_deref().filter(|_| options.persona.is_none() && state.llm.cacheable());
+    let documentation_hash = rag_pipeline.documentation_hash();
+    let ((LlmAnswer { text: answer, usage }, generated), llm_time) = timed_async(tracing::info_span!("llm", elapsed_ms = tracing::field::Empty), async {
+        match request.answer_mode {
+            _ if request.output == Some(OutputKind::Curl) => (LlmAnswer::text(
+                rag_pipeline.curl_answer(&matches).unwrap_or_else(|| NO_RELEVANT_DOCS_ANSWER.to_string()),
+            ), false),
+            _ if request.answer_format == Some(AnswerFormat::Structured) => (LlmAnswer::text(match &structured {
+                Some(structured) => format!("Use {} {} ({}).", structured.method, structured.path, structured.name),
+                None => NO_RELEVANT_DOCS_ANSWER.to_string(),
+            }), false),
+            AnswerMode::Extractive => (LlmAnswer::text(
+                rag_pipeline.extractive_answer(&matches).unwrap_or_else(|| NO_RELEVANT_DOCS_ANSWER.to_string()),
+            ), false),
+            // Use Groq to generate answer from context
+            AnswerMode::Generative if context.trim().is_empty() => (LlmAnswer::text(NO_RELEVANT_DOCS_ANSWER), false),
+            AnswerMode::Generative => match answer_cache.and_then(|cache| cache.get(&request.query, options.deterministic, documentation_hash)) {
+                Some(answer) => (LlmAnswer::text(answer), true),
+                None => {
+                    // Queue behind other LLM calls rather than stampeding the provider
+                    let _permit = state.llm_permits.acquire().await.expect("LLM semaphore is never closed");
+                    let _in_flight = state.metrics.llm_call();
+                    match state.llm.generate_answer(&request.query, &context, &options).await {
+                        Ok(answer) => {
+                            if let Some(cache) = answer_cache
+                                && let Err(e) = cache.insert(&request.query, options.deterministic, documentation_hash, &answer.text).await
+                            {
+                                eprintln!("Warning: could not cache answer: {:#}", e);
+                            }
+                            (answer, true)
+                        }
+                        Err(e) => {
+                            eprintln!("Groq API error: {}", e);
+                            (LlmAnswer::text(format!("I found some relevant information but encountered an error processing it. Here's what I found:\n\n{}", context)), false)
+                        }
+                    }
+                }
+            },
+        }
+    }).await;
+    state.metrics.record_query(usage);
+    // Only the LLM can stray from the docs; extractive, templated and fallback answers quote them
+    let grounding = generated.then(|| grounding_score(&answer, &context));
+
+    // Make returned curl commands target the user's own instance
+    let (answer, structured) = match state.config.freshservice.host() {
+        Some(host) => (
+            apply_domain(&answer, &host),
+            structured.map(|structured| StructuredAnswer {
+                example: structured.example.as_deref().map(|example| apply_domain(example, &host)),
+                ..structured
+            }),
+        ),
+        None => (answer, structured),
+    };
+    // Disclaimers go on every answer, whichever mode produced it
+    let answer = state.config.compliance.wrap_answer(&answer);
+
+    // With several documentation sets, name the ones the context drew from, best match first
+    let sources: Vec<String> = match &routed {
+        Some(routed) => {
+            let mut sources: Vec<String> = Vec::new();
+            for routed in routed.iter().take(rag_pipeline.context_matches(&matches).len()) {
+                if !sources.iter().any(|source| source == routed.source) {
+                    sources.push(routed.source.to_string());
+                }
+            }
+            sources
+        }
+        None => vec!["Freshservice API Documentation".to_string()],
+    };
+
+    Ok(warp::reply::json(&QueryResponse {
+        answer,
+        sources,
+        confidence,
+        confidence_level,
+        grounding,
+        explanation,
+        structured,
+        usage: usage.filter(|_| flags.include_usage),
+        timings: flags.include_timings.then(|| StageTimings::new(retrieval_time, context_time, llm_time)),
+    }).into_response())
+}
+
+fn build_explanation(
+    level: ExplainLevel,
+    rag_pipeline: &RagPipeline,
+    query: &str,
+    matches: &[(&ApiEndpoint, f32)],
+    confidence: f32,
+) -> String {
+    if level == ExplainLevel::None {
+        return String::new();
+    }
+
+    let mut explanation = format!("Found {} relevant endpoints. ", matches.len());
+    if !matches.is_empty() {
+        explanation.push_str(&format!("Best match: '{}' with score {:.2}. ", matches[0].0.name, matches[0].1));
+    }
+    if let Some(intent) = Intent::classify(query) {
+        let methods: Vec<&str> = intent.methods().iter().map(|method| method.as_str()).collect();
+        explanation.push_str(&format!("Detected intent: {} ({}). ", intent.as_str(), methods.join("/")));
+    }
+    explanation.push_str(&format!("Overall confidence: {:.2}", confidence));
+
+    if level == ExplainLevel::Full {
+        let query_lower = query.to_lowercase();
+        explanation.push_str("\nScore breakdown:");
+        for (endpoint, _) in matches {
+            explanation.push_str(&format!(
+                "\n  - {} {} '{}': {}",
+                endpoint.method, endpoint.path, endpoint.name,
+                rag_pipeline.score_breakdown(endpoint, &query_lower)
+            ));
+        }
+    }
+
+    explanation
+}
+
+#[allow(clippy::get_first)]
+async fn handle_debug(if_none_match: Option<String>, state: AppState) -> warp::reply::Response {
+    let snapshot = state.snapshot().await;
+    let rag_pipeline = &snapshot.pipeline;
+    let documentation = rag_pipeline.get_documentation();
+    let endpoint_names: Vec<String> = documentation.endpoints
+        .iter()
+        .map(|e| e.name.clone())
+        .collect();
+
+    cached_json(
+        &serde_json::json!({
+            "total_endpoints": documentation.endpoints.len(),
+            "endpoints": endpoint_names,
+            "sample_endpoint": &documentation.endpoints.get(0)
+        }),
+        rag_pipeline.documentation_hash(),
+        if_none_match.as_deref(),
+    )
+}
+
+async fn handle_endpoints(if_none_match: Option<String>, state: AppState) -> warp::reply::Response {
+    let snapshot = state.snapshot().await;
+    let rag_pipeline = &snapshot.pipeline;
+
+    cached_json(
+        &rag_pipeline.get_documentation().endpoints,
+        rag_pipeline.documentation_hash(),
+        if_none_match.as_deref(),
+    )
+}
+
+/// Checks a would-be request body against the named endpoint's documented parameters.
+async fn handle_validate(name: String, payload: serde_json::Value, state: AppState) -> warp::reply::Response {
+    let name = percent_encoding::percent_decode_str(&name).decode_utf8_lossy();
+    let snapshot = state.snapshot().await;
+    let rag_pipeline = &snapshot.pipeline;
+
+    match rag_pipeline.find_endpoint(&name) {
+        Some(endpoint) => {
+            let errors = endpoint.validate_payload(&payload);
+            warp::reply::json(&serde_json::json!({
+                "endpoint": endpoint.name,
+                "valid": errors.is_empty(),
+                "errors": errors,
+            })).into_response()
+        }
+        None => warp::reply::with_status(
+            warp::reply::json(&serde_json::json!({"error": format!("Unknown endpoint '{}'", name)})),
+            StatusCode::NOT_FOUND,
+        ).into_response(),
+    }
+}
+
+async fn handle_context(params: ContextQuery, if_none_match: Option<String>, state: AppState) -> warp::reply::Response {
+    let snapshot = state.snapshot().await;
+    let rag_pipeline = &snapshot.pipeline;
+    let matches = rag_pipeline.find_relevant_endpoints(&params.q);
+    let (context, max_score) = rag_pipeline.format_context(&matches);
+
+    cached_json(
+        &serde_json::json!({
+            "query": params.q,
+            "context": context,
+            "max_score": max_score,
+        }),
+        rag_pipeline.documentation_hash(),
+        if_none_match.as_deref(),
+    )
+}
+
+async fn handle_search(params: SearchQuery, state: AppState) -> warp::reply::Json {
+    let snapshot = state.snapshot().await;
+    let rag_pipeline = &snapshot.pipeline;
+    let results = rag_pipeline.search(&params.q, params.limit);
+
+    warp::reply::json(&serde_json::json!({
+        "query": params.q,
+        "results": results,
+    }))
+}
+
+/// Example questions for starter chips in a chat UI, derived from the endpoint catalog.
+async fn handle_suggest(state: AppState) -> warp::reply::Json {
+    let snapshot = state.snapshot().await;
+    let rag_pipeline = &snapshot.pipeline;
+    let suggestions: Vec<serde_json::Value> = rag_pipeline.suggested_questions(MAX_SUGGESTIONS)
+        .into_iter()
+        .map(|(question, endpoint)| serde_json::json!({
+            "question": question,
+            "endpoint": endpoint.name,
+        }))
+        .collect();
+
+    warp::reply::json(&serde_json::json!({ "suggestions": suggestions }))
+}
+
+async fn handle_status_endpoints(code: u16, state: AppState) -> warp::reply::Json {
+    let snapshot = state.snapshot().await;
+    let rag_pipeline = &snapshot.pipeline;
+
+    warp::reply::json(&serde_json::json!({
+        "status_code": code,
+        "endpoints": rag_pipeline.endpoints_with_status(code),
+    }))
+}
+
+async fn handle_version(state: AppState) -> warp::reply::Json {
+    let snapshot = state.snapshot().await;
+    let rag_pipeline = &snapshot.pipeline;
+    let documentation = rag_pipeline.get_documentation();
+
+    warp::reply::json(&serde_json::json!({
+        "api_version": documentation.api_version,
+        "changelog": documentation.changelog,
+        "scraped_at": documentation.scraped_at,
+    }))
+}
+
+/// Records a rating of the answer to `query`, attributed to the endpoints that answer would use.
+/// Boosts are recomputed from the log on the next reload or restart.
+async fn handle_feedback(request: FeedbackRequest, state: AppState) -> warp::reply::Response {
+    let endpoints: Vec<String> = {
+        let snapshot = state.snapshot().await;
+        snapshot.pipeline.find_relevant_endpoints(&request.query)
+            .into_iter()
+            .take(state.config.context.max_endpoints)
+            .map(|(endpoint, _)| endpoint.name.clone())
+            .collect()
+    };
+    let entry = FeedbackEntry {
+        query: request.query,
+        helpful: request.helpful,
+        endpoints,
+        recorded_at: chrono::Utc::now(),
+    };
+
+    match append_feedback(&state.config.feedback.log, &entry).await {
+        Ok(()) => warp::reply::json(&serde_json::json!({
+            "status": "recorded",
+            "endpoints": entry.endpoints,
+        })).into_response(),
+        Err(e) => {
+            eprintln!("Failed to record feedback: {}", e);
+            warp::reply::with_status(
+                warp::reply::json(&serde_json::json!({"error": format!("Failed to record feedback: {}", e)})),
+                StatusCode::INTERNAL_SERVER_ERROR,
+            ).into_response()
+        }
+    }
+}
+
+async fn handle_reload(state: AppState) -> warp::reply::Response {
+    match refresh_pipeline(&state).await {
+        Ok((total_endpoints, etag)) => {
+            println!("Reloaded documentation: {} endpoints", total_endpoints);
+
+            warp::reply::json(&serde_json::json!({
+                "status": "reloaded",
+                "total_endpoints": total_endpoints,
+                "etag": etag,
+            })).into_response()
+        }
+        Err(e) => {
+            eprintln!("Reload failed: {}", e);
+            warp::reply::with_status(
+                warp::reply::json(&serde_json::json!({"error": format!("Reload failed: {}", e)})),
+                StatusCode::INTERNAL_SERVER_ERROR,
+            ).into_response()
+        }
+    }
+}
+
+/// Re-scrapes (or, with `--docs-dir`, re-reads the directory) and swaps in the new pipeline,
+/// returning its endpoint count and hash. On failure the current snapshot is left in place.
+async fn refresh_pipeline(state: &AppState) -> Result<(usize, String)> {
+    let snapshot = match &state.docs_dir {
+        Some(dir) => load_doc_sets(dir, &state.config)?,
+        None => Snapshot::new(load_pipeline(&state.config, state.docs.as_deref()).await?),
+    };
+    let total_endpoints = snapshot.pipeline.get_documentation().endpoints.len();
+    let etag = snapshot.pipeline.documentation_hash().to_string();
+    *state.snapshot.write().await = Arc::new(snapshot);
+    Ok((total_endpoints, etag))
+}
+
+/// Background task behind `serve --watch`: refreshes the documentation every `interval`.
+async fn watch_documentation(state: AppState, interval: Duration) {
+    let mut ticker = tokio::time::interval(interval);
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    // The first tick fires immediately, and the pipeline was just loaded at startup
+    ticker.tick().await;
+
+    loop {
+        ticker.tick().await;
+        match refresh_pipeline(&state).await {
+            Ok((total_endpoints, etag)) => {
+                println!("Watch refresh succeeded: {} endpoints (etag {})", total_endpoints, etag)
+            }
+            Err(e) => eprintln!("Watch refresh failed, keeping previous documentation: {}", e),
+        }
+    }
+}
+
+/// Parses a `--watch` interval such as `90`, `30s`, `15m`, or `2h` (bare numbers are seconds).
+pub fn parse_interval(value: &str) -> Result<Duration, String> {
+    let value = value.trim();
+    let split = value.find(|c: char| !c.is_ascii_digit()).unwrap_or(value.len());
+    let (amount, unit) = value.split_at(split);
+    let amount: u64 = amount
+        .parse()
+        .map_err(|_| format!("invalid interval '{}': expected e.g. 30s, 15m or 2h", value))?;
+    let seconds = match unit {
+        "" | "s" => amount,
+        "m" => amount * 60,
+        "h" => amount * 60 * 60,
+        _ => return Err(format!("invalid interval unit '{}': use s, m or h", unit)),
+    };
+    if seconds == 0 {
+        return Err("interval must be greater than zero".to_string());
+    }
+    Ok(Duration::from_secs(seconds))
+}
+
+/// Replies with `value` as JSON tagged with the documentation hash, or `304 Not Modified`
+/// when the client already holds the current version.
+fn cached_json<T: Serialize>(value: &T, documentation_hash: &str, if_none_match: Option<&str>) -> warp::reply::Response {
+    let etag = format!("\"{}\"", documentation_hash);
+    let not_modified = if_none_match.is_some_and(|header| {
+        header.split(',').any(|tag| {
+            let tag = tag.trim();
+            tag == "*" || tag.trim_start_matches("W/") == etag
+        })
+    });
+
+    let mut response = if not_modified {
+        StatusCode::NOT_MODIFIED.into_response()
+    } else {
+        warp::reply::json(value).into_response()
+    };
+
+    let headers = response.headers_mut();
+    if let Ok(value) = header::HeaderValue::from_str(&etag) {
+        headers.insert(header::ETAG, value);
+    }
+    headers.insert(header::CACHE_CONTROL, header::HeaderValue::from_static("public, no-cache"));
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{ApiParameter, HttpMethod, ScrapedDocumentation};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn test_state() -> AppState {
+        let documentation = ScrapedDocumentation {
+            base_url: "https://api.freshservice.com".to_string(),
+            endpoints: vec![ApiEndpoint {
+                name: "Create Ticket".to_string(),
+                description: "Create a new ticket".to_string(),
+                method: HttpMethod::Post,
+                path: "/api/v2/tickets".to_string(),
+                ..Default::default()
+            }],
+            scraped_at: chrono::Utc::now(),
+            ..Default::default()
+        };
+
+        AppState {
+            snapshot: Arc::new(RwLock::new(Arc::new(Snapshot::new(RagPipeline::new(documentation))))),
+            llm: Arc::new(EchoContextClient),
+            config: Arc::new(Config::default()),
+            metrics: Arc::new(Metrics::default()),
+            llm_permits: Arc::new(Semaphore::new(Config::default().llm.max_concurrent_requests)),
+            docs_dir: None,
+            docs: None,
+            answer_cache: None,
+        }
+    }
+
+    #[test]
+    fn test_parse_interval() {
+        assert_eq!(parse_interval("90"), Ok(Duration::from_secs(90)));
+        assert_eq!(parse_interval("30s"), Ok(Duration::from_secs(30)));
+        assert_eq!(parse_interval("15m"), Ok(Duration::from_secs(15 * 60)));
+        assert_eq!(parse_interval("2h"), Ok(Duration::from_secs(2 * 60 * 60)));
+        assert!(parse_interval("0s").is_err());
+        assert!(parse_interval("10d").is_err());
+        assert!(parse_interval("soon").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_query_route_end_to_end_with_mock_llm() {
+        let state = AppState {
+            llm: Arc::new(MockLlmProvider::new(None)),
+            ..test_state()
+        };
+        let filter = routes(state);
+
+        let response = warp::test::request()
+            .method("POST")
+            .path("/query")
+            .json(&serde_json::json!({"query": "How do I create a ticket?"}))
+            .reply(&filter)
+            .await;
+        let body: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        let answer = body["answer"].as_str().unwrap();
+        assert!(answer.starts_with("Mock answer for 'How do I create a ticket?'"));
+        assert!(answer.contains("Path: /api/v2/tickets"));
+        assert_eq!(body["sources"], serde_json::json!(["Freshservice API Documentation"]));
+        let confidence = body["confidence"].as_f64().unwrap();
+        assert!((0.1..=1.0).contains(&confidence));
+        assert!(["high", "medium", "low"].contains(&body["confidence_level"].as_str().unwrap()));
+        assert!(body["explanation"].as_str().unwrap().contains("Best match: 'Create Ticket'"));
+        assert!((0.0..=1.0).contains(&body["grounding"].as_f64().unwrap()));
+    }
+
+    struct MeteredProvider;
+
+    #[async_trait::async_trait]
+    impl LlmProvider for MeteredProvider {
+        async fn generate_answer(&self, _query: &str, _context: &str, _options: &GenerationOptions) -> Result<LlmAnswer> {
+            Ok(LlmAnswer {
+                text: "Use POST /api/v2/tickets".to_string(),
+                usage: Some(TokenUsage { prompt: 100, completion: 20, total: 120 }),
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_usage_is_opt_in_and_aggregated_in_metrics() {
+        let state = AppState { llm: Arc::new(MeteredProvider), ..test_state() };
+        let filter = routes(state);
+        let query = serde_json::json!({"query": "create ticket"});
+
+        let plain = warp::test::request().method("POST").path("/query").json(&query).reply(&filter).await;
+        let plain: serde_json::Value = serde_json::from_slice(plain.body()).unwrap();
+        assert!(plain.get("usage").is_none());
+
+        let with_usage = warp::test::request().method("POST").path("/query?include_usage=true").json(&query).reply(&filter).await;
+        let with_usage: serde_json::Value = serde_json::from_slice(with_usage.body()).unwrap();
+        assert_eq!(with_usage["usage"], serde_json::json!({"prompt": 100, "completion": 20, "total": 120}));
+
+        let metrics = warp::test::request().path("/metrics").reply(&filter).await;
+        let metrics: serde_json::Value = serde_json::from_slice(metrics.body()).unwrap();
+        assert_eq!(metrics["queries"], 2);
+        assert_eq!(metrics["tokens"]["total"], 240);
+    }
+
+    #[tokio::test]
+    async fn test_validate_route() {
+        let mut state = test_state();
+        let mut documentation = state.snapshot().await.pipeline.get_documentation().clone();
+        documentation.endpoints[0].parameters = vec![crate::models::ApiParameter {
+            name: "email".to_string(),
+            required: true,
+            ..Default::default()
+        }];
+        state.snapshot = Arc::new(RwLock::new(Arc::new(Snapshot::new(RagPipeline::new(documentation)))));
+        let filter = routes(state);
+
+        let invalid = warp::test::request()
+            .method("POST")
+            .path("/endpoints/Create%20Ticket/validate")
+            .json(&serde_json::json!({"subject": "Printer on fire"}))
+            .reply(&filter)
+            .await;
+        let body: serde_json::Value = serde_json::from_slice(invalid.body()).unwrap();
+        assert_eq!(body["valid"], false);
+        assert_eq!(body["errors"], serde_json::json!([{"kind": "missing_required", "field": "email"}]));
+
+        let valid = warp::test::request()
+            .method("POST")
+            .path("/endpoints/create%20ticket/validate")
+            .json(&serde_json::json!({"email": "a@b.com"}))
+            .reply(&filter)
+            .await;
+        let body: serde_json::Value = serde_json::from_slice(valid.body()).unwrap();
+        assert_eq!(body["valid"], true);
+
+        let unknown = warp::test::request()
+            .method("POST")
+            .path("/endpoints/Nope/validate")
+            .json(&serde_json::json!({}))
+            .reply(&filter)
+            .await;
+        assert_eq!(unknown.status(), StatusCode::NOT_FOUND);
+    }
+
+    struct SlowProvider;
+
+    #[async_trait::async_trait]
+    impl LlmProvider for SlowProvider {
+        async fn generate_answer(&self, _query: &str, _context: &str, _options: &GenerationOptions) -> Result<LlmAnswer> {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            Ok(LlmAnswer::text("Use POST /api/v2/tickets."))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_timings_cover_each_stage() {
+        let filter = routes(AppState { llm: Arc::new(SlowProvider), ..test_state() });
+        let request = || warp::test::request().method("POST").json(&serde_json::json!({"query": "create ticket"}));
+
+        let response = request().path("/query?include_timings=true").reply(&filter).await;
+        let body: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+        let timings = &body["timings"];
+        let stage = |name: &str| timings[name].as_f64().unwrap_or_else(|| panic!("missing {}", name));
+
+        assert!(stage("retrieval_ms") > 0.0);
+        assert!(stage("context_ms") > 0.0);
+        assert!(stage("llm_ms") >= 20.0);
+        assert!(stage("llm_ms") > stage("retrieval_ms") && stage("llm_ms") > stage("context_ms"));
+        let sum = stage("retrieval_ms") + stage("context_ms") + stage("llm_ms");
+        assert!((stage("total_ms") - sum).abs() < 1e-6);
+
+        let response = request().path("/query").reply(&filter).await;
+        let body: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+        assert!(body.get("timings").is_none());
+    }
+
+    #[derive(Default)]
+    struct CountingProvider {
+        calls: std::sync::atomic::AtomicUsize,
+    }
+
+    #[async_trait::async_trait]
+    impl LlmProvider for CountingProvider {
+        async fn generate_answer(&self, _query: &str, _context: &str, _options: &GenerationOptions) -> Result<LlmAnswer> {
+            let call = self.calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst) + 1;
+            Ok(LlmAnswer::text(format!("answer {}", call)))
+        }
+
+        fn cacheable(&self) -> bool {
+            true
+        }
+    }
+
+    #[tokio::test]
+    async fn test_answer_cache_is_invalidated_by_reload_with_changed_docs() {
+        let dir = std::env::temp_dir().join(format!("freshservice-rag-answer-cache-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let provider = Arc::new(CountingProvider::default());
+        let state = AppState {
+            llm: provider.clone(),
+            answer_cache: Some(Arc::new(AnswerCache::open(&dir).unwrap())),
+            ..test_state()
+        };
+        let filter = routes(state.clone());
+        let ask = |query: &'static str| {
+            let filter = filter.clone();
+            async move {
+                let response = warp::test::request().method("POST").path("/query").json(&serde_json::json!({"query": query})).reply(&filter).await;
+                let body: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+                body["answer"].as_str().unwrap().to_string()
+            }
+        };
+
+        assert_eq!(ask("How do I create a ticket?").await, "answer 1");
+        assert_eq!(ask("how do i create a  ticket?").await, "answer 1");
+
+        // A re-scrape of unchanged docs only moves the timestamps, which keeps the cache
+        {
+            let mut snapshot = state.snapshot.write().await;
+            let mut documentation = snapshot.pipeline.get_documentation().clone();
+            documentation.scraped_at = chrono::Utc::now();
+            documentation.endpoints[0].last_verified = Some(chrono::Utc::now());
+            *snapshot = Arc::new(Snapshot::new(RagPipeline::new(documentation)));
+        }
+        assert_eq!(ask("How do I create a ticket?").await, "answer 1");
+
+        // Same effect as /reload picking up changed documentation
+        {
+            let mut snapshot = state.snapshot.write().await;
+            let mut documentation = snapshot.pipeline.get_documentation().clone();
+            documentation.endpoints[0].description = "Create a new ticket on behalf of a requester".to_string();
+            *snapshot = Arc::new(Snapshot::new(RagPipeline::new(documentation)));
+        }
+        assert_eq!(ask("How do I create a ticket?").await, "answer 2");
+        assert_eq!(provider.calls.load(std::sync::atomic::Ordering::SeqCst), 2);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_echoed_answers_are_not_cached() {
+        let dir = std::env::temp_dir().join(format!("freshservice-rag-echo-cache-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let state = AppState {
+            answer_cache: Some(Arc::new(AnswerCache::open(&dir).unwrap())),
+            ..test_state()
+        };
+
+        let response = warp::test::request()
+            .method("POST")
+            .path("/query")
+            .json(&serde_json::json!({"query": "How do I create a ticket?"}))
+            .reply(&routes(state))
+            .await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(!dir.join("answers.json").exists());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    struct UnreachableProvider;
+
+    #[async_trait::async_trait]
+    impl LlmProvider for UnreachableProvider {
+        async fn generate_answer(&self, _query: &str, _context: &str, _options: &GenerationOptions) -> Result<LlmAnswer> {
+            panic!("extractive mode must not call the LLM");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_extractive_mode_skips_llm() {
+        let state = AppState { llm: Arc::new(UnreachableProvider), ..test_state() };
+
+        let response = warp::test::request()
+            .method("POST")
+            .path("/query")
+            .json(&serde_json::json!({"query": "create ticket", "answer_mode": "extractive"}))
+            .reply(&routes(state))
+            .await;
+        let body: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        let answer = body["answer"].as_str().unwrap();
+        assert!(answer.starts_with("Use POST /api/v2/tickets (Create Ticket)."));
+        assert!(answer.contains("Description: Create a new ticket"));
+        assert!(!answer.ends_with("---"));
+        assert!(body.get("grounding").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_curl_output_returns_example_verbatim() {
+        let curl = r#"curl -v -u api_key:X -H "Content-Type: application/json" -d '{"email": "tom@outerspace.com"}' -X POST 'https://domain.freshservice.com/api/v2/tickets'"#;
+        let state = test_state();
+        {
+            let mut snapshot = state.snapshot.write().await;
+            let mut documentation = snapshot.pipeline.get_documentation().clone();
+            documentation.endpoints[0].curl_example = Some(curl.to_string());
+            documentation.endpoints.push(ApiEndpoint {
+                name: "Delete Ticket".to_string(),
+                description: "Delete a ticket".to_string(),
+                method: HttpMethod::Delete,
+                path: "/api/v2/tickets/{id}".to_string(),
+                ..Default::default()
+            });
+            *snapshot = Arc::new(Snapshot::new(RagPipeline::new(documentation)));
+        }
+        let filter = routes(AppState { llm: Arc::new(UnreachableProvider), ..state });
+
+        let answer_for = |query: &str| {
+            let request = warp::test::request()
+                .method("POST")
+                .path("/query")
+                .json(&serde_json::json!({"query": query, "output": "curl"}));
+            let filter = filter.clone();
+            async move {
+                let body: serde_json::Value = serde_json::from_slice(request.reply(&filter).await.body()).unwrap();
+                body["answer"].as_str().unwrap().to_string()
+            }
+        };
+
+        assert_eq!(answer_for("give me the curl for creating a ticket").await, curl);
+        assert_eq!(
+            answer_for("delete a ticket").await,
+            "Delete Ticket (DELETE /api/v2/tickets/{id}) best matches your question, but its documentation has no curl example."
+        );
+    }
+
+    #[tokio::test]
+    async fn test_structured_format_describes_top_match() {
+        let state = test_state();
+        {
+            let mut snapshot = state.snapshot.write().await;
+            let mut documentation = snapshot.pipeline.get_documentation().clone();
+            let param = |name: &str, required: bool| ApiParameter { name: name.to_string(), required, ..Default::default() };
+            documentation.endpoints[0].parameters = vec![param("email", true), param("subject", true), param("cc_emails", false)];
+            documentation.endpoints[0].curl_example = Some("curl -X POST 'https://domain.freshservice.com/api/v2/tickets'".to_string());
+            *snapshot = Arc::new(Snapshot::new(RagPipeline::new(documentation)));
+        }
+        let filter = routes(AppState { llm: Arc::new(UnreachableProvider), ..state });
+
+        let response = warp::test::request()
+            .method("POST")
+            .path("/query")
+            .json(&serde_json::json!({"query": "how do I create a ticket", "answer_format": "structured"}))
+            .reply(&filter)
+            .await;
+        let body: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+
+        assert_eq!(body["answer"], "Use POST /api/v2/tickets (Create Ticket).");
+        let structured = &body["structured"];
+        assert_eq!(structured["method"], "POST");
+        assert_eq!(structured["path"], "/api/v2/tickets");
+        assert_eq!(
+            structured["required_params"],
+            serde_json::json!(["email", "subject"])
+        );
+        assert_eq!(structured["example"], "curl -X POST 'https://domain.freshservice.com/api/v2/tickets'");
+    }
+
+    struct OptionsEchoProvider;
+
+    #[async_trait::async_trait]
+    impl LlmProvider for OptionsEchoProvider {
+        async fn generate_answer(&self, _query: &str, _context: &str, options: &GenerationOptions) -> Result<LlmAnswer> {
+            Ok(LlmAnswer::text(format!("deterministic={} persona={:?}", options.deterministic, options.persona)))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_deterministic_flag_reaches_provider() {
+        let filter = routes(AppState { llm: Arc::new(OptionsEchoProvider), ..test_state() });
+
+        for (request, expected) in [
+            (serde_json::json!({"query": "create ticket"}), "deterministic=false persona=None"),
+            (serde_json::json!({"query": "create ticket", "deterministic": true}), "deterministic=true persona=None"),
+        ] {
+            let response = warp::test::request().method("POST").path("/query").json(&request).reply(&filter).await;
+            let body: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+            assert_eq!(body["answer"], expected);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_persona_is_resolved_from_config() {
+        let filter = routes(AppState { llm: Arc::new(OptionsEchoProvider), ..test_state() });
+
+        let response = warp::test::request()
+            .method("POST")
+            .path("/query")
+            .json(&serde_json::json!({"query": "create ticket", "persona": "beginner"}))
+            .reply(&filter)
+            .await;
+        let body: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+        let expected = Config::default().persona("beginner").unwrap().to_string();
+        assert_eq!(body["answer"], format!("deterministic=false persona={:?}", Some(expected)));
+
+        let unknown = warp::test::request()
+            .method("POST")
+            .path("/query")
+            .json(&serde_json::json!({"query": "create ticket", "persona": "pirate"}))
+            .reply(&filter)
+            .await;
+        let body: serde_json::Value = serde_json::from_slice(unknown.body()).unwrap();
+        assert_eq!(unknown.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(body["available"], serde_json::json!(["beginner", "developer", "support-agent"]));
+    }
+
+    #[tokio::test]
+    async fn test_docs_dir_query_reports_source_set() {
+        let dir = std::env::temp_dir().join(format!("freshservice-rag-docs-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        for (set, name, path) in [("assets", "Create Asset", "/api/v2/assets"), ("tickets", "Create Ticket", "/api/v2/tickets")] {
+            let documentation = ScrapedDocumentation {
+                endpoints: vec![ApiEndpoint {
+                    name: name.to_string(),
+                    description: format!("{} in the service desk", name),
+                    method: HttpMethod::Post,
+                    path: path.to_string(),
+                    ..Default::default()
+                }],
+                ..Default::default()
+            };
+            std::fs::write(dir.join(format!("{}.json", set)), serde_json::to_string(&documentation).unwrap()).unwrap();
+        }
+
+        let config = Config::default();
+        let state = AppState {
+            snapshot: Arc::new(RwLock::new(Arc::new(load_doc_sets(&dir, &config).unwrap()))),
+            docs_dir: Some(dir.clone()),
+            ..test_state()
+        };
+        let filter = routes(state);
+
+        let response = warp::test::request()
+            .method("POST")
+            .path("/query")
+            .json(&serde_json::json!({"query": "create asset"}))
+            .reply(&filter)
+            .await;
+        let body: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+        // The ticket set matches "create" too, but too weakly to make it into the context
+        assert_eq!(body["sources"], serde_json::json!(["assets"]));
+        assert!(body["answer"].as_str().unwrap().contains("Create Asset"));
+
+        let endpoints = warp::test::request().path("/endpoints").reply(&filter).await;
+        let endpoints: serde_json::Value = serde_json::from_slice(endpoints.body()).unwrap();
+        assert_eq!(endpoints.as_array().unwrap().len(), 2);
+
+        // A reload swaps the sets and the merged catalog together
+        let documentation = ScrapedDocumentation {
+            endpoints: vec![ApiEndpoint {
+                name: "Create Problem".to_string(),
+                description: "Create Problem in the service desk".to_string(),
+                method: HttpMethod::Post,
+                path: "/api/v2/problems".to_string(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        std::fs::write(dir.join("problems.json"), serde_json::to_string(&documentation).unwrap()).unwrap();
+        let reload = warp::test::request().method("POST").path("/reload").reply(&filter).await;
+        let reload: serde_json::Value = serde_json::from_slice(reload.body()).unwrap();
+        assert_eq!(reload["total_endpoints"], 3);
+        let response = warp::test::request()
+            .method("POST")
+            .path("/query")
+            .json(&serde_json::json!({"query": "create problem"}))
+            .reply(&filter)
+            .await;
+        let body: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+        assert_eq!(body["sources"], serde_json::json!(["problems"]));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_suggest_references_real_endpoints() {
+        let response = warp::test::request().path("/suggest").reply(&routes(test_state())).await;
+        let body: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+
+        let suggestions = body["suggestions"].as_array().unwrap();
+        assert!(!suggestions.is_empty());
+        assert_eq!(suggestions[0]["question"], "How do I create a ticket?");
+        assert_eq!(suggestions[0]["endpoint"], "Create Ticket");
+    }
+
+    struct PeakConcurrencyProvider {
+        current: AtomicUsize,
+        peak: AtomicUsize,
+    }
+
+    #[async_trait::async_trait]
+    impl LlmProvider for PeakConcurrencyProvider {
+        async fn generate_answer(&self, _query: &str, _context: &str, _options: &GenerationOptions) -> Result<LlmAnswer> {
+            let now = self.current.fetch_add(1, Ordering::SeqCst) + 1;
+            self.peak.fetch_max(now, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(30)).await;
+            self.current.fetch_sub(1, Ordering::SeqCst);
+            Ok(LlmAnswer::text("Use POST /api/v2/tickets"))
+        }
+    }
+
+    /// Holds every answer until `release` is notified, signalling `entered` once a call starts.
+    #[derive(Default)]
+    struct GatedProvider {
+        entered: tokio::sync::Notify,
+        release: tokio::sync::Notify,
+    }
+
+    #[async_trait::async_trait]
+    impl LlmProvider for GatedProvider {
+        async fn generate_answer(&self, _query: &str, _context: &str, _options: &GenerationOptions) -> Result<LlmAnswer> {
+            self.entered.notify_one();
+            self.release.notified().await;
+            Ok(LlmAnswer::text("Use POST /api/v2/tickets"))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_swap_does_not_wait_for_in_flight_llm_call() {
+        let provider = Arc::new(GatedProvider::default());
+        let state = AppState { llm: provider.clone(), ..test_state() };
+        let filter = routes(state.clone());
+        let query = tokio::spawn(async move {
+            warp::test::request()
+                .method("POST")
+                .path("/query")
+                .json(&serde_json::json!({"query": "create ticket"}))
+                .reply(&filter)
+                .await
+        });
+        provider.entered.notified().await;
+
+        let swap = tokio::time::timeout(Duration::from_secs(1), state.snapshot.write()).await;
+        assert!(swap.is_ok(), "the pipeline lock was held across the LLM call");
+        drop(swap);
+
+        provider.release.notify_one();
+        assert_eq!(query.await.unwrap().status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_llm_concurrency_is_capped() {
+        let provider = Arc::new(PeakConcurrencyProvider { current: AtomicUsize::new(0), peak: AtomicUsize::new(0) });
+        let filter = routes(AppState {
+            llm: provider.clone(),
+            llm_permits: Arc::new(Semaphore::new(2)),
+            ..test_state()
+        });
+
+        let requests = (0..6).map(|_| {
+            warp::test::request()
+                .method("POST")
+                .path("/query")
+                .json(&serde_json::json!({"query": "create ticket"}))
+                .reply(&filter)
+        });
+        let responses = futures::future::join_all(requests).await;
+
+        assert!(responses.iter().all(|response| response.status() == StatusCode::OK));
+        assert_eq!(provider.peak.load(Ordering::SeqCst), 2);
+        let metrics = warp::test::request().path("/metrics").reply(&filter).await;
+        let metrics: serde_json::Value = serde_json::from_slice(metrics.body()).unwrap();
+        assert_eq!(metrics["llm_in_flight"], 0);
+    }
+
+    #[tokio::test]
+    async fn test_gzip_only_when_accepted() {
+        let state = test_state();
+        {
+            let mut snapshot = state.snapshot.write().await;
+            let mut documentation = snapshot.pipeline.get_documentation().clone();
+            documentation.endpoints[0].description = "Create a new ticket. ".repeat(500);
+            *snapshot = Arc::new(Snapshot::new(RagPipeline::new(documentation)));
+        }
+        let filter = gzip_when_accepted(routes(state));
+
+        let gzipped = warp::test::request()
+            .path("/endpoints")
+            .header("accept-encoding", "br, gzip")
+            .reply(&filter)
+            .await;
+        let plain = warp::test::request().path("/endpoints").reply(&filter).await;
+
+        assert_eq!(gzipped.status(), StatusCode::OK);
+        assert_eq!(gzipped.headers()[header::CONTENT_ENCODING], "gzip");
+        assert!(gzipped.body().len() < plain.body().len() / 10);
+        assert!(plain.headers().get(header::CONTENT_ENCODING).is_none());
+        serde_json::from_slice::<serde_json::Value>(plain.body()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_gzip_runs_rejecting_routes_once() {
+        let evaluations = Arc::new(AtomicUsize::new(0));
+        let counted = evaluations.clone();
+        let filter = warp::any()
+            .map(move || counted.fetch_add(1, Ordering::SeqCst))
+            .and(warp::path!("only"))
+            .map(|_| "ok");
+
+        let response = warp::test::request()
+            .path("/missing")
+            .header("accept-encoding", "gzip")
+            .reply(&gzip_when_accepted(filter))
+            .await;
+
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(evaluations.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_explanation_levels() {
+        let state = test_state();
+        let snapshot = state.snapshot().await;
+        let pipeline = &snapshot.pipeline;
+        let matches = pipeline.find_relevant_endpoints("create ticket");
+
+        let none = build_explanation(ExplainLevel::None, pipeline, "create ticket", &matches, 0.8);
+        let short = build_explanation(ExplainLevel::Short, pipeline, "create ticket", &matches, 0.8);
+        let full = build_explanation(ExplainLevel::Full, pipeline, "create ticket", &matches, 0.8);
+
+        assert!(none.is_empty());
+        assert_eq!(short, format!("Found 1 relevant endpoints. Best match: 'Create Ticket' with score {:.2}. Detected intent: create (POST). Overall confidence: 0.80", matches[0].1));
+        assert!(full.starts_with(&short));
+        assert!(full.contains("POST /api/v2/tickets 'Create Ticket': "));
+        assert!(full.contains("name "));
+
+        let request: QueryRequest = serde_json::from_str(r#"{"query": "x", "explain": "full"}"#).unwrap();
+        assert_eq!(request.explain, Some(ExplainLevel::Full));
+        let request: QueryRequest = serde_json::from_str(r#"{"query": "x"}"#).unwrap();
+        assert_eq!(request.explain.unwrap_or_default(), ExplainLevel::Short);
+    }
+
+    #[tokio::test]
+    async fn test_endpoints_include_confidence() {
+        let response = warp::test::request().path("/endpoints").reply(&routes(test_state())).await;
+        let body: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+
+        assert!(body[0]["confidence"].is_number());
+        assert!(body[0].get("last_verified").is_some());
+    }
+
+    #[tokio::test]
+    async fn test_unchanged_catalog_returns_not_modified() {
+        let filter = routes(test_state());
+
+        let first = warp::test::request().path("/endpoints").reply(&filter).await;
+        assert_eq!(first.status(), StatusCode::OK);
+        assert_eq!(first.headers()[header::CACHE_CONTROL], "public, no-cache");
+        let etag = first.headers()[header::ETAG].to_str().unwrap().to_string();
+
+        let second = warp::test::request()
+            .path("/endpoints")
+            .header("if-none-match", &etag)
+            .reply(&filter)
+            .await;
+        assert_eq!(second.status(), StatusCode::NOT_MODIFIED);
+        assert!(second.body().is_empty());
+
+        let stale = warp::test::request()
+            .path("/debug")
+            .header("if-none-match", "\"0000000000000000\"")
+            .reply(&filter)
+            .await;
+        assert_eq!(stale.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_search_route_returns_ranked_results() {
+        let filter = routes(test_state());
+
+        let response = warp::test::request().path("/search?q=create%20ticket&limit=5").reply(&filter).await;
+        let body: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(body["results"][0]["name"], "Create Ticket");
+        assert_eq!(body["results"][0]["method"], "POST");
+        assert_eq!(body["results"][0]["snippet"], "Create a new ticket");
+    }
+
+    #[tokio::test]
+    async fn test_etag_changes_when_catalog_is_swapped() {
+        let state = test_state();
+        let filter = routes(state.clone());
+        let before = warp::test::request().path("/context?q=ticket").reply(&filter).await;
+
+        let mut documentation = state.snapshot().await.pipeline.get_documentation().clone();
+        documentation.endpoints[0].description = "Raise a new ticket".to_string();
+        *state.snapshot.write().await = Arc::new(Snapshot::new(RagPipeline::new(documentation)));
+        let after = warp::test::request().path("/context?q=ticket").reply(&filter).await;
+
+        assert_ne!(before.headers()[header::ETAG], after.headers()[header::ETAG]);
+    }
+
+    #[tokio::test]
+    async fn test_feedback_is_logged_with_matched_endpoints() {
+        let log = std::env::temp_dir().join(format!("freshservice-rag-feedback-{}.jsonl", std::process::id()));
+        let _ = std::fs::remove_file(&log);
+        let mut config = Config::default();
+        config.feedback.log = log.clone();
+        let state = AppState { config: Arc::new(config), ..test_state() };
+
+        let response = warp::test::request()
+            .method("POST")
+            .path("/feedback")
+            .json(&serde_json::json!({"query": "create ticket", "helpful": true}))
+            .reply(&routes(state))
+            .await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let logged: FeedbackEntry = serde_json::from_str(std::fs::read_to_string(&log).unwrap().trim()).unwrap();
+        std::fs::remove_file(&log).unwrap();
+        assert!(logged.helpful);
+        assert_eq!(logged.endpoints, vec!["Create Ticket".to_string()]);
+    }
+}
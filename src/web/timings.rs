@@ -0,0 +1,52 @@
+use serde::Serialize;
+use std::future::Future;
+use std::time::{Duration, Instant};
+use tracing::Instrument;
+
+/// Time spent in each stage of a `/query`, in milliseconds. `total_ms` is the sum of the
+/// stages, so the remainder of the request (confidence, explanation, serialization) is excluded.
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct StageTimings {
+    pub retrieval_ms: f64,
+    pub context_ms: f64,
+    pub llm_ms: f64,
+    pub total_ms: f64,
+}
+
+impl StageTimings {
+    pub fn new(retrieval: Duration, context: Duration, llm: Duration) -> Self {
+        Self {
+            retrieval_ms: millis(retrieval),
+            context_ms: millis(context),
+            llm_ms: millis(llm),
+            total_ms: millis(retrieval + context + llm),
+        }
+    }
+}
+
+/// Runs `stage` inside `span` and records how long it took in the span's `elapsed_ms` field.
+pub fn timed<T>(span: tracing::Span, stage: impl FnOnce() -> T) -> (T, Duration) {
+    let start = Instant::now();
+    let output = span.in_scope(stage);
+    let elapsed = start.elapsed();
+    record(&span, elapsed);
+    (output, elapsed)
+}
+
+/// [`timed`] for a stage that awaits, such as the LLM call.
+pub async fn timed_async<F: Future>(span: tracing::Span, stage: F) -> (F::Output, Duration) {
+    let start = Instant::now();
+    let output = stage.instrument(span.clone()).await;
+    let elapsed = start.elapsed();
+    record(&span, elapsed);
+    (output, elapsed)
+}
+
+fn record(span: &tracing::Span, elapsed: Duration) {
+    span.record("elapsed_ms", millis(elapsed));
+    tracing::debug!(parent: span, elapsed_ms = millis(elapsed), "stage finished");
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}